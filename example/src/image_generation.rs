@@ -4,18 +4,16 @@ use nn_yandex_art::models::request::aspect_ratio::AspectRatioBuilder;
 use nn_yandex_art::models::request::generation_options::GenerationOptionsBuilder;
 use nn_yandex_art::models::request::types::ImageType;
 use nn_yandex_art::models::request::RequestBuilder;
-use anyhow;
 use tokio::time::{sleep, Duration};
 use std::fs::File;
 use std::io::Write;
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine;
-use std::env;
+use nn_yandex_core::config::Config;
 
 pub async fn generate_image(prompt: &str, path: &str, width_ratio: i64, height_ratio: i64) -> Result<(), anyhow::Error>{
 
-    let BUCKET = env::var("BUCKET")?;
-    let API_KEY = env::var("API")?;
+    let config = Config::from_env()?;
 
     let message = MessageBuilder::new()
         .text(prompt)
@@ -26,7 +24,7 @@ pub async fn generate_image(prompt: &str, path: &str, width_ratio: i64, height_r
     let aspect_ratio = AspectRatioBuilder::new()
         .width_ratio(width_ratio)
         .height_ratio(height_ratio)
-        .build();
+        .build()?;
 
     let generation_options = GenerationOptionsBuilder::new()
         .aspect_ratio(aspect_ratio)
@@ -39,7 +37,7 @@ pub async fn generate_image(prompt: &str, path: &str, width_ratio: i64, height_r
         .message(message)
         .build()?;
 
-    let art = Art::new(API_KEY, BUCKET);
+    let art = Art::from_config(&config)?;
     let mut res = art.generate_image(request).await?;
     let id = res.id;
 
@@ -1,8 +1,11 @@
+#[allow(dead_code)]
 mod image_generation;
+#[allow(dead_code)]
 mod text_generation;
 
 #[tokio::main]
-async fn main() {
-    text_generation::start_chating().await;
+async fn main() -> Result<(), anyhow::Error> {
+    dotenvy::dotenv().ok();
+    text_generation::start_chating().await
 }
 
@@ -1,11 +1,11 @@
 use nn_yandex_gpt::{TextGenerator, ModelType, Version};
 use nn_yandex_gpt::models::request::{RequestBuilder, CompletionOptions};
 use nn_yandex_gpt::models::message::{MessageBuilder, Role};
+use nn_yandex_core::config::Config;
 
 pub async fn generate_text(text: &str, prompt: &str) -> Result<String, anyhow::Error> {
 
-    let BUCKET = "bucket".to_string();
-    let API_KEY = "api".to_string();
+    let config = Config::from_env()?;
 
     let prompt_message = MessageBuilder::new()
         .with_role(Role::System)
@@ -27,22 +27,21 @@ pub async fn generate_text(text: &str, prompt: &str) -> Result<String, anyhow::E
         .with_completion_options(opts)
         .build();
 
-    let generator = TextGenerator::new(API_KEY, BUCKET);
-    let result = generator.complete(ModelType::GptPro, Version::RC, req).await;
+    let generator = TextGenerator::from_config(&config)?;
+    let result = generator.complete((ModelType::GptPro, Version::RC), req).await;
     match result {
-        Ok(result) => {
-            let alt = result.result.alternatives[0].clone();
-            Ok(alt.message.text)
-        },
+        Ok(result) => result
+            .text()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Response contains no alternatives")),
         Err(err) => Err(anyhow::Error::new(err)),
     }
 }
 
-pub async fn start_chating(){
+pub async fn start_chating() -> Result<(), anyhow::Error> {
     use std::io::{stdin,stdout,Write};
 
-    let BUCKET = "bucket".to_string();
-    let API_KEY = "api".to_string();
+    let config = Config::from_env()?;
 
     let prompt_message = MessageBuilder::new()
         .with_role(Role::System)
@@ -63,7 +62,7 @@ pub async fn start_chating(){
         .with_completion_options(opts)
         .build();
 
-    let generator = TextGenerator::new(API_KEY, BUCKET);
+    let generator = TextGenerator::from_config(&config)?;
 
     println!("Ассистент: Чем я могу вам помочь?", );
     loop {
@@ -76,12 +75,14 @@ pub async fn start_chating(){
             .with_text(&s)
             .build();
         req.messages.push(user_message);
-        let result = generator.complete(ModelType::GptPro, Version::RC, req.clone()).await;
+        let result = generator.complete((ModelType::GptPro, Version::RC), req.clone()).await;
         match result {
-            Ok(result) => {
-                let alt = result.result.alternatives[0].clone();
-                req.messages.push(alt.message.clone());
-                println!("Ассистент: {}", alt.message.text);
+            Ok(result) => match result.alternative() {
+                Some(alt) => {
+                    req.messages.push(alt.message.clone());
+                    println!("Ассистент: {}", alt.message.text);
+                }
+                None => println!("Ответ пуст"),
             },
             Err(err) => println!("{err}"),
         }
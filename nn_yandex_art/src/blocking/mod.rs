@@ -0,0 +1,239 @@
+//! Synchronous client for programs without an async runtime (`blocking` feature)
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use nn_yandex_core::auth::{Auth, CredentialCell};
+use nn_yandex_core::blocking::BlockingRuntime;
+use nn_yandex_core::config::Config;
+use nn_yandex_core::error::ConfigError;
+use nn_yandex_core::middleware::Middleware;
+use nn_yandex_core::retry::RetryPolicy;
+use nn_yandex_core::secret::Secret;
+use nn_yandex_core::transport::HttpTransport;
+use nn_yandex_core::usage::UsageTracker;
+
+use crate::error::ArtError;
+use crate::models::model_type::ArtModel;
+use crate::models::request::Request;
+use crate::models::response::Response;
+use crate::variations::{GeneratedImage, SeedStrategy, VariationError, VariationOptions};
+
+/// Blocking counterpart of `nn_yandex_art::Art` using the same request and response types
+///
+/// Must not be used from inside an async runtime; use the async client there.
+#[derive(Clone)]
+pub struct Art {
+    inner: crate::Art,
+    runtime: BlockingRuntime,
+}
+
+impl Art {
+    /// Creates a new Art instance
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - Your Yandex API key
+    /// * `bucket_id` - The bucket ID for the model
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime cannot be created
+    pub fn new(api_key: impl Into<Secret>, bucket_id: String) -> Self {
+        crate::Art::new(api_key, bucket_id).into()
+    }
+
+    /// Creates an Art instance from a Config, see `nn_yandex_art::Art::from_config`
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Shared client configuration
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        crate::Art::from_config(config).map(Self::from)
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.inner = self.inner.with_retry_policy(retry_policy);
+        self
+    }
+
+    pub fn with_default_model(mut self, model: impl Into<ArtModel>) -> Self {
+        self.inner = self.inner.with_default_model(model);
+        self
+    }
+
+    pub fn default_model(&self) -> &ArtModel {
+        self.inner.default_model()
+    }
+
+    pub fn with_usage_tracker(mut self, tracker: Arc<UsageTracker>) -> Self {
+        self.inner = self.inner.with_usage_tracker(tracker);
+        self
+    }
+
+    pub fn with_trace_content(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_trace_content(enabled);
+        self
+    }
+
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.inner = self.inner.with_transport(transport);
+        self
+    }
+
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.inner = self.inner.with_middleware(middleware);
+        self
+    }
+
+    pub fn with_credentials(mut self, credentials: CredentialCell) -> Self {
+        self.inner = self.inner.with_credentials(credentials);
+        self
+    }
+
+    pub fn credentials(&self) -> CredentialCell {
+        self.inner.credentials()
+    }
+
+    pub fn rotate_credentials(&self, auth: Auth) {
+        self.inner.rotate_credentials(auth);
+    }
+
+    /// Changes API key and bucket ID
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - New API key
+    /// * `bucket_id` - New bucket ID
+    #[deprecated(note = "detaches the client from its shared credential cell; use `rotate_credentials` to change the key, or build a new client for another folder")]
+    pub fn change_credentials(&mut self, api_key: impl Into<Secret>, bucket_id: String) {
+        #[allow(deprecated)]
+        self.inner.change_credentials(api_key, bucket_id);
+    }
+
+    /// Returns the underlying async client
+    pub fn as_async(&self) -> &crate::Art {
+        &self.inner
+    }
+
+    /// Starts an image generation operation
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Request data for image generation
+    ///
+    /// # Returns
+    ///
+    /// `Result<Response, ArtError>` - The response with `id` and `done`-flag or error
+    pub fn generate_image(&self, request: Request) -> Result<Response, ArtError> {
+        self.runtime.block_on(self.inner.generate_image(request))
+    }
+
+    /// Checks the status of an image generation operation
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - ID of the request from `response` returned by `generate_image`
+    ///
+    /// # Returns
+    ///
+    /// `Result<Response, ArtError>` - Current operation status or error
+    pub fn check_operation(&self, request_id: &str) -> Result<Response, ArtError> {
+        self.runtime.block_on(self.inner.check_operation(request_id))
+    }
+
+    /// Cancels an image generation operation
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - ID of the request from `response` returned by `generate_image`
+    ///
+    /// # Returns
+    ///
+    /// `Result<Response, ArtError>` - Operation status after cancellation or error
+    pub fn cancel_operation(&self, request_id: &str) -> Result<Response, ArtError> {
+        self.runtime.block_on(self.inner.cancel_operation(request_id))
+    }
+
+    /// Blocks until an image generation operation is done
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - ID of the request from `response` returned by `generate_image`
+    /// * `poll_interval` - Delay between status checks
+    /// * `timeout` - How long to wait; the operation keeps running after a timeout
+    ///
+    /// # Returns
+    ///
+    /// `Result<Response, ArtError>` - Finished operation, `ArtError::Timeout` or another error
+    pub fn wait_for_operation(
+        &self,
+        request_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<Response, ArtError> {
+        self.runtime.block_on(self.inner.wait_for_operation(request_id, poll_interval, timeout))
+    }
+
+    /// Generates a variation of the request for every seed and blocks until all are done
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Base request; its seed is replaced by each seed of `seeds`
+    /// * `seeds` - Seed list, number of random seeds or seed range, at most `MAX_SEEDS`
+    /// * `options` - Concurrency, poll interval and timeout of each operation
+    ///
+    /// # Returns
+    ///
+    /// `Result<Vec<Result<GeneratedImage, VariationError>>, ArtError>` - One result per seed,
+    /// in seed order, or the error that kept the sweep from starting
+    pub fn generate_variations(
+        &self,
+        request: Request,
+        seeds: impl Into<SeedStrategy>,
+        options: VariationOptions,
+    ) -> Result<Vec<Result<GeneratedImage, VariationError>>, ArtError> {
+        self.runtime.block_on(self.inner.generate_variations(request, seeds, options))
+    }
+}
+
+impl From<crate::Art> for Art {
+    /// Wraps an async client, creating the internal runtime
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime cannot be created
+    fn from(inner: crate::Art) -> Self {
+        Art { inner, runtime: BlockingRuntime::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::request::RequestBuilder;
+    use crate::models::request::aspect_ratio::AspectRatioBuilder;
+    use crate::models::request::types::ImageType;
+    use crate::models::request::generation_options::GenerationOptionsBuilder;
+    use crate::models::request::message::MessageBuilder;
+    use nn_yandex_core::usage::{Budget, PriceTable};
+
+    #[test]
+    fn test_budget_error_without_network() {
+        let tracker = Arc::new(UsageTracker::new(PriceTable::default()).with_budget(Budget { soft: None, hard: Some(0.0) }));
+        let art = Art::new("key", "folder".to_string()).with_usage_tracker(tracker);
+        let request = RequestBuilder::new()
+            .message(MessageBuilder::new().text("fox").build().unwrap())
+            .generation_options(
+                GenerationOptionsBuilder::new()
+                    .mime_type(ImageType::Png)
+                    .aspect_ratio(AspectRatioBuilder::new().width_ratio(1).height_ratio(1).build().unwrap())
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        assert!(matches!(art.generate_image(request), Err(ArtError::Budget(_))));
+    }
+}
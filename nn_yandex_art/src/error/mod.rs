@@ -1,17 +1,66 @@
-#[derive(Debug)]
-pub struct BuildError {
-    message: String,
-}
+use nn_yandex_core::error::{BudgetExceeded, InvalidCredentials};
+use nn_yandex_core::http::StatusCode;
+use nn_yandex_core::middleware::MiddlewareError;
+use nn_yandex_core::secret::redact;
+use nn_yandex_core::transport::TransportError;
+use nn_yandex_core::telemetry::ErrorCode;
 
-impl BuildError {
-    pub fn new(message: &str) -> Self {
-        BuildError { message: message.to_string() }
-    }
+use crate::models::response::ErrorWrapper;
+
+/// Errors returned by the request builders
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// Message text is empty
+    MissingText,
+    /// The request has no messages
+    NoMessages,
+    /// The request has more messages than the API accepts
+    TooManyMessages { count: usize, max: usize },
+    /// The request has no generation options
+    MissingGenerationOptions,
+    /// Generation options have no mime type
+    MissingMimeType,
+    /// Generation options have no aspect ratio
+    MissingAspectRatio,
+    /// An aspect ratio side or image dimension is zero or negative
+    NonPositiveAspectRatio { width: i64, height: i64 },
+    /// Aspect ratio is outside the supported range
+    AspectRatioOutOfRange { width: i64, height: i64 },
+    /// Unknown aspect ratio preset name
+    UnknownPreset(String),
+    /// Unknown style preset name
+    UnknownStyle(String),
+    /// Model name or version is empty or contains whitespace or `/`
+    InvalidModel(String),
+    /// A seed sweep has more seeds than allowed
+    TooManySeeds { count: usize, max: usize },
+    /// Operation ID is empty or contains characters other than ASCII letters, digits, `-` and `_`
+    InvalidOperationId(String),
 }
 
 impl std::fmt::Display for BuildError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+        match self {
+            BuildError::MissingText => write!(f, "Text is required"),
+            BuildError::NoMessages => write!(f, "No messages added"),
+            BuildError::TooManyMessages { count, max } => {
+                write!(f, "{} messages added, at most {} are allowed", count, max)
+            }
+            BuildError::MissingGenerationOptions => write!(f, "No generation options added"),
+            BuildError::MissingMimeType => write!(f, "Mime type is required"),
+            BuildError::MissingAspectRatio => write!(f, "Aspect ratio is required"),
+            BuildError::NonPositiveAspectRatio { width, height } => {
+                write!(f, "Aspect ratio {}:{} must be positive", width, height)
+            }
+            BuildError::AspectRatioOutOfRange { width, height } => {
+                write!(f, "Aspect ratio {}:{} is outside the supported range 1:4 to 4:1", width, height)
+            }
+            BuildError::UnknownPreset(name) => write!(f, "Unknown aspect ratio preset: {}", name),
+            BuildError::UnknownStyle(name) => write!(f, "Unknown style: {}", name),
+            BuildError::InvalidModel(segment) => write!(f, "Invalid model name or version: {}", segment),
+            BuildError::TooManySeeds { count, max } => write!(f, "{} seeds requested, at most {} are allowed", count, max),
+            BuildError::InvalidOperationId(id) => write!(f, "Invalid operation ID: {}", id),
+        }
     }
 }
 
@@ -20,23 +69,79 @@ impl std::error::Error for BuildError {}
 /// Errors returned by the Art library
 #[derive(Debug)]
 pub enum ArtError {
-    /// HTTP request error
-    Http(reqwest::Error),
+    /// The request could not be sent or the response could not be received
+    Http(TransportError),
     /// Error returned by Yandex API
     Api(String),
+    /// Non-success HTTP status whose body could not be parsed as an API error
+    Status(StatusCode),
+    /// Non-success HTTP status with an API error, e.g. the field violations of a 400
+    Rejected { status: StatusCode, error: ErrorWrapper },
+    /// The operation finished with an error; typed details are in `ErrorWrapper`
+    Operation(ErrorWrapper),
     /// Operation is not yet finished
     NotReady,
+    /// The operation with this ID did not finish before the wait timed out
+    Timeout(String),
     /// Response field is missing in the result
     MissingResponse,
+    /// The input of a call is invalid; nothing was sent
+    Build(BuildError),
+    /// The usage tracker's hard budget is spent; the request was not sent
+    Budget(BudgetExceeded),
+    /// A middleware rejected the request
+    Middleware(MiddlewareError),
+    /// The credentials cannot be sent; the request was not sent
+    Auth(InvalidCredentials),
 }
 
 impl std::fmt::Display for ArtError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ArtError::Http(e) => write!(f, "HTTP error: {}", e),
-            ArtError::Api(msg) => write!(f, "API error: {}", msg),
+            ArtError::Http(e) => write!(f, "HTTP error: {}", redact(&e.to_string())),
+            ArtError::Api(msg) => write!(f, "API error: {}", redact(msg)),
+            ArtError::Status(status) => write!(f, "request failed with status: {}", status),
+            ArtError::Rejected { status, error } => write!(f, "API error ({}): {}", status, redact(&error.message)),
+            ArtError::Operation(e) => write!(f, "API error: {}", redact(&e.message)),
             ArtError::NotReady => write!(f, "Operation not finished"),
+            ArtError::Timeout(id) => write!(f, "Operation {} did not finish in time", id),
             ArtError::MissingResponse => write!(f, "Response missing"),
+            ArtError::Build(e) => write!(f, "{}", e),
+            ArtError::Budget(e) => write!(f, "{}", e),
+            ArtError::Middleware(e) => write!(f, "Rejected by middleware: {}", e),
+            ArtError::Auth(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl ArtError {
+    /// Returns `true` for connection failures, timeouts, rate limiting and server-side
+    /// errors, after which the request may succeed when repeated
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ArtError::Http(e) => e.is_timeout() || e.is_connect(),
+            ArtError::Status(status) | ArtError::Rejected { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ErrorCode for ArtError {
+    fn error_code(&self) -> String {
+        match self {
+            ArtError::Http(_) => "http".to_string(),
+            ArtError::Api(_) => "api".to_string(),
+            ArtError::Status(status) | ArtError::Rejected { status, .. } => status.as_str().to_string(),
+            ArtError::Operation(e) => e.code.to_string(),
+            ArtError::NotReady => "not_ready".to_string(),
+            ArtError::Timeout(_) => "timeout".to_string(),
+            ArtError::MissingResponse => "missing_response".to_string(),
+            ArtError::Build(_) => "build".to_string(),
+            ArtError::Budget(_) => "budget".to_string(),
+            ArtError::Middleware(_) => "middleware".to_string(),
+            ArtError::Auth(_) => "auth".to_string(),
         }
     }
 }
@@ -47,13 +152,19 @@ mod tests {
 
     #[test]
     fn test_build_error_display() {
-        let err = BuildError::new("Something went wrong");
-        assert_eq!(format!("{}", err), "Something went wrong");
+        let err = BuildError::TooManyMessages { count: 11, max: 10 };
+        assert_eq!(format!("{}", err), "11 messages added, at most 10 are allowed");
     }
 
     #[test]
     fn test_build_error_debug() {
-        let err = BuildError::new("Debug test");
-        assert_eq!(format!("{:?}", err), "BuildError { message: \"Debug test\" }");
+        let err = BuildError::TooManyMessages { count: 11, max: 10 };
+        assert_eq!(format!("{:?}", err), "TooManyMessages { count: 11, max: 10 }");
+    }
+
+    #[test]
+    fn test_art_error_redacts_credentials() {
+        let err = ArtError::Api("Failed to parse JSON. Response text: Authorization: Api-Key AQVN123".to_string());
+        assert_eq!(err.to_string(), "API error: Failed to parse JSON. Response text: Authorization: Api-Key [REDACTED]");
     }
 }
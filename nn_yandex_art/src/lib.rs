@@ -1,10 +1,28 @@
 pub mod models;
 pub mod error;
+pub mod variations;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 
+use models::model_type::ArtModel;
 use models::request::Request;
-use models::response::Response;
+use models::response::{ErrorWrapper, Response};
+use futures::stream::{self, StreamExt};
 use std::future::Future;
-use crate::error::ArtError;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::error::{ArtError, BuildError};
+use crate::variations::{GeneratedImage, SeedStrategy, VariationError, VariationOptions};
+use nn_yandex_core::auth::{Auth, CredentialCell};
+use nn_yandex_core::config::Config;
+use nn_yandex_core::error::ConfigError;
+use nn_yandex_core::http::HttpRequest;
+use nn_yandex_core::middleware::{Middleware, MiddlewareStack};
+use nn_yandex_core::retry::RetryPolicy;
+use nn_yandex_core::secret::Secret;
+use nn_yandex_core::transport::{HttpTransport, ReqwestTransport};
+use nn_yandex_core::telemetry::{CallSpan, Endpoint};
+use nn_yandex_core::usage::UsageTracker;
 
 const YANDEX_ART_URL: &str = "https://llm.api.cloud.yandex.net/foundationModels/v1/imageGenerationAsync";
 const YANDEX_GET_OPERATION: &str = "https://operation.api.cloud.yandex.net/operations";
@@ -14,10 +32,26 @@ const YANDEX_GET_OPERATION: &str = "https://operation.api.cloud.yandex.net/opera
 impl std::error::Error for ArtError {}
 
 /// Main structure for generating images with Yandex Art API
+///
+/// Cloning is cheap: clones share the HTTP client, credentials, middleware and usage
+/// tracker. Builder methods called on a clone only affect that clone.
+#[derive(Clone)]
 pub struct Art {
-    api_key: String,
+    inner: Arc<Inner>,
+}
+
+#[derive(Clone)]
+struct Inner {
+    auth: CredentialCell,
     bucket_id: String,
-    client: reqwest::Client,
+    transport: Arc<dyn HttpTransport>,
+    image_generation_url: String,
+    operations_url: String,
+    retry_policy: RetryPolicy,
+    default_model: ArtModel,
+    usage_tracker: Option<Arc<UsageTracker>>,
+    trace_content: bool,
+    middleware: MiddlewareStack,
 }
 
 impl Art {
@@ -27,12 +61,152 @@ impl Art {
     ///
     /// * `api_key` - Your Yandex API key
     /// * `bucket_id` - The bucket ID for the model
-    pub fn new(api_key: String, bucket_id: String) -> Self {
-        Self {
-            api_key,
+    pub fn new(api_key: impl Into<Secret>, bucket_id: String) -> Self {
+        Self::from_inner(Inner {
+            auth: CredentialCell::new(Auth::api_key(api_key)),
             bucket_id,
-            client: reqwest::Client::new(),
-        }
+            transport: Arc::new(ReqwestTransport::default()),
+            image_generation_url: YANDEX_ART_URL.to_string(),
+            operations_url: YANDEX_GET_OPERATION.to_string(),
+            retry_policy: RetryPolicy::none(),
+            default_model: ArtModel::default(),
+            usage_tracker: None,
+            trace_content: false,
+            middleware: MiddlewareStack::new(),
+        })
+    }
+
+    /// Creates an Art instance from a Config
+    ///
+    /// Uses the configured auth method, image generation and operations endpoints,
+    /// timeouts and retry policy
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Shared client configuration
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        Ok(Self::from_inner(Inner {
+            auth: CredentialCell::new(config.auth.clone()),
+            bucket_id: config.folder_id.clone(),
+            transport: Arc::new(ReqwestTransport::new(config.http_client()?)),
+            image_generation_url: config
+                .endpoints
+                .image_generation
+                .clone()
+                .unwrap_or_else(|| YANDEX_ART_URL.to_string()),
+            operations_url: config
+                .endpoints
+                .operations
+                .clone()
+                .unwrap_or_else(|| YANDEX_GET_OPERATION.to_string()),
+            retry_policy: config.retry_policy.clone(),
+            default_model: ArtModel::default(),
+            usage_tracker: None,
+            trace_content: false,
+            middleware: MiddlewareStack::new(),
+        }))
+    }
+
+    fn from_inner(inner: Inner) -> Self {
+        Art { inner: Arc::new(inner) }
+    }
+
+    /// Returns the settings for modification, copying them if they are shared with a clone
+    fn inner_mut(&mut self) -> &mut Inner {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// Sets the retry policy for connection failures and timeouts (no retries by default)
+    ///
+    /// # Arguments
+    ///
+    /// * `retry_policy` - Retry policy to apply to every call
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.inner_mut().retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets the model used by requests that do not choose one (`yandex-art/latest` by default)
+    ///
+    /// # Arguments
+    ///
+    /// * `model` - Model and version, e.g. `Version::RC` or `"yandex-art/rc".parse()?`
+    pub fn with_default_model(mut self, model: impl Into<ArtModel>) -> Self {
+        self.inner_mut().default_model = model.into();
+        self
+    }
+
+    /// Returns the model used by requests that do not choose one
+    pub fn default_model(&self) -> &ArtModel {
+        &self.inner.default_model
+    }
+
+    /// Records every started image generation in `tracker` and rejects new generations
+    /// with `ArtError::Budget` once its hard budget is spent
+    ///
+    /// # Arguments
+    ///
+    /// * `tracker` - Usage tracker, possibly shared with other clients
+    pub fn with_usage_tracker(mut self, tracker: Arc<UsageTracker>) -> Self {
+        self.inner_mut().usage_tracker = Some(tracker);
+        self
+    }
+
+    /// Logs image prompts as TRACE events of the `generate_image` span
+    /// (requires the `tracing` feature; off by default)
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether prompts may be logged
+    pub fn with_trace_content(mut self, enabled: bool) -> Self {
+        self.inner_mut().trace_content = enabled;
+        self
+    }
+
+    /// Sends requests through `transport` instead of the default reqwest client
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - HTTP transport, e.g. `InMemoryTransport` in tests
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.inner_mut().transport = Arc::new(transport);
+        self
+    }
+
+    /// Adds a middleware run around every HTTP call, retries and polling included
+    ///
+    /// # Arguments
+    ///
+    /// * `middleware` - Hooks to run before and after each request
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.inner_mut().middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Uses a shared credential cell, e.g. one also given to a `TextGenerator`
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials` - Credential cell shared with other clients
+    pub fn with_credentials(mut self, credentials: CredentialCell) -> Self {
+        self.inner_mut().auth = credentials;
+        self
+    }
+
+    /// Returns a handle to this client's credentials that a secrets watcher can
+    /// `rotate` while requests are running
+    pub fn credentials(&self) -> CredentialCell {
+        self.inner.auth.clone()
+    }
+
+    /// Replaces the credentials without exclusive access; in-flight requests are not
+    /// interrupted and clients sharing the credential cell are rotated as well
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - New credentials
+    pub fn rotate_credentials(&self, auth: Auth) {
+        self.inner.auth.rotate(auth);
     }
 
     /// Changes API key and bucket ID
@@ -41,45 +215,65 @@ impl Art {
     ///
     /// * `api_key` - New API key
     /// * `bucket_id` - New bucket ID
-    pub fn change_credentials(&mut self, api_key: String, bucket_id: String) {
-        self.api_key = api_key;
-        self.bucket_id = bucket_id;
+    ///
+    /// This client gets a new credential cell: handles from `credentials()` and clients
+    /// sharing the old cell keep the old key and are no longer rotated with this client.
+    #[deprecated(note = "detaches the client from its shared credential cell; use `rotate_credentials` to change the key, or build a new client for another folder")]
+    pub fn change_credentials(&mut self, api_key: impl Into<Secret>, bucket_id: String) {
+        self.inner_mut().auth = CredentialCell::new(Auth::api_key(api_key));
+        self.inner_mut().bucket_id = bucket_id;
     }
 
     /// Sends a request to generate an image asynchronously
     ///
     /// # Arguments
     ///
-    /// * `request` - Request data for image generation
+    /// * `request` - Request data for image generation; sent to its own model or the
+    ///   client's default model
     ///
     /// # Returns
     ///
-    /// `Result<Response, ArtError>` - The response with `id` and `done`-flag or error
-    pub fn generate_image(
+    /// `Result<Response, ArtError>` - The response with `id` and `done`-flag or error.
+    /// `model_version()` of the finished operation tells which version generated the image
+    pub fn generate_image(&self, request: Request) -> impl Future<Output = Result<Response, ArtError>> + Send + 'static {
+        self.start_generation(request, true)
+    }
+
+    /// Starts an image generation; with `charge` unset the usage tracker is neither checked
+    /// nor updated because the caller has reserved the generation already
+    fn start_generation(
         &self,
         mut request: Request,
-    ) -> impl Future<Output = Result<Response, ArtError>> + '_ {
-        request.model_uri = format!("art://{}/yandex-art/latest", self.bucket_id);
-        let client = &self.client;
-        let api_key = &self.api_key;
-
-        async move {
-            let resp = client
-                .post(YANDEX_ART_URL)
-                .header("Authorization", format!("Api-Key {}", api_key))
-                .json(&request)
-                .send()
-                .await
-                .map_err(ArtError::Http)?;
+        charge: bool,
+    ) -> impl Future<Output = Result<Response, ArtError>> + Send + 'static {
+        request.model_uri = self.model_uri(&request);
 
-            let result: Response = resp.json().await.map_err(ArtError::Http)?;
+        let inner = self.inner.clone();
+        let span = CallSpan::new(Endpoint::GenerateImage, inner.trace_content).with_model_uri(&request.model_uri);
 
-            if let Some(err) = &result.error {
-                return Err(ArtError::Api(err.message.clone()));
+        span.run(move |span| async move {
+            let tracker = inner.usage_tracker.as_ref().filter(|_| charge);
+            if let Some(tracker) = tracker {
+                tracker.check().map_err(ArtError::Budget)?;
             }
+            span.trace_content("prompt", || serde_json::to_string(&request.messages).unwrap_or_default());
+
+            let http_request = HttpRequest::post(&inner.image_generation_url)
+                .with_json(&request)
+                .map_err(|e| ArtError::Api(format!("Failed to serialize request: {e}")))?;
+            let result = inner.with_retries(&span, || inner.call(&http_request, &span)).await?;
 
+            span.record_operation_id(&result.id);
+            if let Some(tracker) = tracker {
+                tracker.record_images(&request.model_uri, 1);
+            }
             Ok(result)
-        }
+        })
+    }
+
+    /// Returns the URI of the request's own model or the client's default model
+    fn model_uri(&self, request: &Request) -> String {
+        request.model.as_ref().unwrap_or(&self.inner.default_model).resolve(&self.inner.bucket_id)
     }
 
     /// Checks the status of an image generation operation
@@ -90,34 +284,519 @@ impl Art {
     ///
     /// # Returns
     ///
-    /// `Result<Response, ArtError>` - Current operation status or error
+    /// `Result<Response, ArtError>` - Current operation status or error; `ArtError::Build`
+    /// if `request_id` is not a valid operation ID
     pub fn check_operation(
         &self,
         request_id: &str,
-    ) -> impl Future<Output = Result<Response, ArtError>> + '_ {
-        let client = &self.client;
-        let api_key = &self.api_key;
+    ) -> impl Future<Output = Result<Response, ArtError>> + Send + 'static {
+        let inner = self.inner.clone();
+        let url = inner.operation_url(request_id, "");
+        let span = CallSpan::new(Endpoint::CheckOperation, inner.trace_content);
+        span.record_operation_id(request_id);
+
+        span.run(move |span| async move {
+            let http_request = HttpRequest::get(url?);
+            let result = inner
+                .with_retries(&span, || inner.call(&http_request, &span))
+                .await?;
+
+            if result.done
+                && let Some(duration) = result.elapsed()
+            {
+                span.record_operation_duration(duration);
+            }
+            Ok(result)
+        })
+    }
+
+    /// Cancels an image generation operation
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - ID of the request from `response` returned by `generate_image`
+    ///
+    /// # Returns
+    ///
+    /// `Result<Response, ArtError>` - Operation status after cancellation or error;
+    /// `ArtError::Build` if `request_id` is not a valid operation ID
+    pub fn cancel_operation(
+        &self,
+        request_id: &str,
+    ) -> impl Future<Output = Result<Response, ArtError>> + Send + 'static {
+        let inner = self.inner.clone();
+        let url = inner.operation_url(request_id, ":cancel");
+        let span = CallSpan::new(Endpoint::CancelOperation, inner.trace_content);
+        span.record_operation_id(request_id);
+
+        span.run(move |span| async move {
+            let http_request = HttpRequest::post(url?);
+            inner.with_retries(&span, || inner.call(&http_request, &span))
+                .await
+        })
+    }
+
+    /// Polls an image generation operation until it is done or `timeout` passes
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - ID of the request from `response` returned by `generate_image`
+    /// * `poll_interval` - Delay between status checks
+    /// * `timeout` - How long to wait; the operation keeps running after a timeout
+    ///
+    /// # Returns
+    ///
+    /// `Result<Response, ArtError>` - Finished operation, `ArtError::Timeout` or another error
+    pub fn wait_for_operation(
+        &self,
+        request_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<Response, ArtError>> + Send + 'static {
         let request_id = request_id.to_string();
+        let art = self.clone();
 
         async move {
-            let resp = client
-                .get(format!("{YANDEX_GET_OPERATION}/{request_id}"))
-                .header("Authorization", format!("Api-Key {}", api_key))
-                .send()
-                .await
-                .map_err(ArtError::Http)?;
+            let deadline = tokio::time::Instant::now() + timeout;
+            loop {
+                let result = art.check_operation(&request_id).await?;
+                if result.done {
+                    return Ok(result);
+                }
+                let now = tokio::time::Instant::now();
+                if now >= deadline {
+                    return Err(ArtError::Timeout(request_id));
+                }
+                tokio::time::sleep(poll_interval.min(deadline - now)).await;
+            }
+        }
+    }
 
-            let text = resp.text().await.map_err(ArtError::Http)?;
+    /// Generates a variation of the request for every seed and waits for all of them
+    ///
+    /// Every seed starts a separate operation; at most `options.concurrency()` of them run
+    /// at once. The usage tracker is charged for all seeds before the first one is sent, and
+    /// seeds whose generation could not be started are given back. A failed seed does not
+    /// stop the others
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Base request; its seed is replaced by each seed of `seeds`
+    /// * `seeds` - Seed list, number of random seeds or seed range, at most `MAX_SEEDS`
+    /// * `options` - Concurrency, poll interval and timeout of each operation
+    ///
+    /// # Returns
+    ///
+    /// `Result<Vec<Result<GeneratedImage, VariationError>>, ArtError>` - One result per seed,
+    /// in seed order; `ArtError::Build` for too many seeds and `ArtError::Budget` when the
+    /// sweep does not fit in the hard budget, in which case nothing is sent
+    pub fn generate_variations(
+        &self,
+        request: Request,
+        seeds: impl Into<SeedStrategy>,
+        options: VariationOptions,
+    ) -> impl Future<Output = Result<Vec<Result<GeneratedImage, VariationError>>, ArtError>> + Send + 'static {
+        let art = self.clone();
+        let seeds = seeds.into().seeds();
+        async move {
+            let seeds = seeds.map_err(ArtError::Build)?;
+            let model_uri = art.model_uri(&request);
+            if let Some(tracker) = &art.inner.usage_tracker {
+                tracker.reserve_images(&model_uri, seeds.len() as u64).map_err(ArtError::Budget)?;
+            }
 
-            let result: Response = serde_json::from_str(&text).map_err(|e| {
-                ArtError::Api(format!("Failed to parse JSON: {e}. Response text: {text}"))
-            })?;
+            let variations = seeds.into_iter().map(|seed| {
+                let art = art.clone();
+                let request = request.clone().with_seed(seed);
+                async move {
+                    art.generate_variation(request, options)
+                        .await
+                        .map_err(|error| VariationError { seed, error })
+                }
+            });
+            Ok(stream::iter(variations).buffered(options.concurrency()).collect().await)
+        }
+    }
 
-            if let Some(err) = &result.error {
-                return Err(ArtError::Api(err.message.clone()));
+    async fn generate_variation(&self, request: Request, options: VariationOptions) -> Result<GeneratedImage, ArtError> {
+        let operation = match self.start_generation(request.clone(), false).await {
+            Ok(operation) => operation,
+            Err(error) => {
+                if let Some(tracker) = &self.inner.usage_tracker {
+                    tracker.release_images(&self.model_uri(&request), 1);
+                }
+                return Err(error);
             }
+        };
+        let result = if operation.done {
+            operation
+        } else {
+            self.wait_for_operation(&operation.id, options.poll_interval(), options.timeout()).await?
+        };
+        let response = result.response.ok_or(ArtError::MissingResponse)?;
 
-            Ok(result)
+        Ok(GeneratedImage {
+            seed: request.seed().unwrap_or_default(),
+            request,
+            operation_id: result.id,
+            image: response.image,
+            model_version: response.model_version,
+        })
+    }
+}
+
+impl Inner {
+    /// Returns the URL of an operation followed by `suffix`
+    ///
+    /// Operation IDs consist of ASCII letters, digits, `-` and `_`; anything else could
+    /// change the path or query of the URL and is rejected
+    fn operation_url(&self, request_id: &str, suffix: &str) -> Result<String, ArtError> {
+        let valid = !request_id.is_empty()
+            && request_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ArtError::Build(BuildError::InvalidOperationId(request_id.to_string())));
         }
+        Ok(format!("{}/{request_id}{suffix}", self.operations_url))
+    }
+
+    /// Authorizes and sends a request through the middleware and parses the operation
+    async fn call(&self, request: &HttpRequest, span: &CallSpan) -> Result<Response, ArtError> {
+        let mut request = request
+            .clone()
+            .with_auth(&self.auth.load())
+            .map_err(ArtError::Auth)?;
+        self.middleware.before_request(&mut request).map_err(ArtError::Middleware)?;
+
+        let result = self.exchange(&request, span).await;
+        if let Err(e) = &result {
+            self.middleware.on_error(&request, e);
+        }
+        result
+    }
+
+    async fn exchange(&self, request: &HttpRequest, span: &CallSpan) -> Result<Response, ArtError> {
+        let mut resp = self.transport.send(request).await.map_err(ArtError::Http)?;
+        span.record_status(resp.status.as_u16());
+        self.middleware.after_response(request, &mut resp);
+
+        if !resp.status.is_success() {
+            return Err(match resp.json::<ErrorWrapper>() {
+                Ok(error) => ArtError::Rejected { status: resp.status, error },
+                Err(_) => ArtError::Status(resp.status),
+            });
+        }
+
+        let result: Response = resp.json().map_err(|e| {
+            ArtError::Api(format!("Failed to parse JSON: {e}. Response text: {}", resp.text()))
+        })?;
+
+        if let Some(err) = result.error {
+            return Err(ArtError::Operation(err));
+        }
+
+        Ok(result)
+    }
+
+    /// Repeats `call` while it fails with a retryable error and the retry policy allows it
+    async fn with_retries<F, Fut>(&self, span: &CallSpan, call: F) -> Result<Response, ArtError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<Response, ArtError>>,
+    {
+        let mut retry = 0;
+        loop {
+            match call().await {
+                Err(e) if e.is_retryable() && retry < self.retry_policy.max_retries() => {
+                    tokio::time::sleep(self.retry_policy.backoff(retry)).await;
+                    retry += 1;
+                }
+                result => {
+                    span.record_retries(retry);
+                    return result;
+                }
+            }
+        }
+    }
+}
+
+// Compile-time guarantee that public types can be shared between threads and tasks
+const _: () = {
+    use models::model_type::{ModelType, Version};
+    use models::request::RequestBuilder;
+    use models::request::aspect_ratio::{AspectRatio, AspectRatioBuilder, AspectRatioPreset};
+    use models::request::generation_options::{GenerationOptions, GenerationOptionsBuilder};
+    use models::request::message::{Message, MessageBuilder};
+    use models::request::prompt::{Style, WeightedPrompt};
+    use models::request::types::ImageType;
+    use models::response::ResponseWrapper;
+    use models::response::error_details::{
+        BadRequest, ErrorDetail, ErrorInfo, FieldViolation, OperationMetadata, QuotaFailure, QuotaViolation, RequestInfo,
+        RetryInfo,
+    };
+
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Art>();
+    #[cfg(feature = "blocking")]
+    assert_send_sync::<blocking::Art>();
+    assert_send_sync::<ArtError>();
+    assert_send_sync::<BuildError>();
+    assert_send_sync::<Request>();
+    assert_send_sync::<ArtModel>();
+    assert_send_sync::<ModelType>();
+    assert_send_sync::<Version>();
+    assert_send_sync::<RequestBuilder>();
+    assert_send_sync::<Message>();
+    assert_send_sync::<MessageBuilder>();
+    assert_send_sync::<WeightedPrompt>();
+    assert_send_sync::<Style>();
+    assert_send_sync::<GenerationOptions>();
+    assert_send_sync::<GenerationOptionsBuilder>();
+    assert_send_sync::<AspectRatio>();
+    assert_send_sync::<AspectRatioBuilder>();
+    assert_send_sync::<AspectRatioPreset>();
+    assert_send_sync::<ImageType>();
+    assert_send_sync::<Response>();
+    assert_send_sync::<ResponseWrapper>();
+    assert_send_sync::<ErrorWrapper>();
+    assert_send_sync::<ErrorDetail>();
+    assert_send_sync::<BadRequest>();
+    assert_send_sync::<FieldViolation>();
+    assert_send_sync::<QuotaFailure>();
+    assert_send_sync::<QuotaViolation>();
+    assert_send_sync::<RetryInfo>();
+    assert_send_sync::<ErrorInfo>();
+    assert_send_sync::<RequestInfo>();
+    assert_send_sync::<OperationMetadata>();
+    assert_send_sync::<SeedStrategy>();
+    assert_send_sync::<GeneratedImage>();
+    assert_send_sync::<VariationError>();
+    assert_send_sync::<VariationOptions>();
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::model_type::{ModelType, Version};
+    use models::request::RequestBuilder;
+    use models::request::aspect_ratio::AspectRatioBuilder;
+    use models::request::generation_options::GenerationOptionsBuilder;
+    use models::request::message::MessageBuilder;
+    use models::request::types::ImageType;
+    use nn_yandex_core::http::{HttpResponse, Method, StatusCode};
+    use nn_yandex_core::transport::InMemoryTransport;
+
+    fn request() -> Request {
+        RequestBuilder::new()
+            .message(MessageBuilder::new().text("red fox").build().unwrap())
+            .generation_options(
+                GenerationOptionsBuilder::new()
+                    .mime_type(ImageType::Png)
+                    .aspect_ratio(AspectRatioBuilder::new().width_ratio(1).height_ratio(1).build().unwrap())
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    fn ok(body: &str) -> Result<HttpResponse, nn_yandex_core::transport::TransportError> {
+        Ok(HttpResponse::new(StatusCode::OK, body))
+    }
+
+    #[tokio::test]
+    async fn test_generate_and_wait() {
+        let transport = Arc::new(InMemoryTransport::from_responses([
+            ok(r#"{"id":"op1","done":false}"#),
+            ok(r#"{"id":"op1","done":false}"#),
+            ok(r#"{"id":"op1","done":true,"response":{"image":"aW1n","modelVersion":"1"}}"#),
+        ]));
+        let art = Art::new("key", "folder".to_string()).with_transport(transport.clone());
+
+        let operation = art.generate_image(request()).await.unwrap();
+        let result = art.wait_for_operation(&operation.id, Duration::ZERO, Duration::from_secs(5)).await.unwrap();
+
+        assert_eq!(result.model_version(), Some("1"));
+        assert_eq!(result.response.unwrap().image, "aW1n");
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, Method::POST);
+        let body: serde_json::Value = serde_json::from_slice(requests[0].body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["modelUri"], "art://folder/yandex-art/latest");
+        assert_eq!(requests[1].url, format!("{YANDEX_GET_OPERATION}/op1"));
+    }
+
+    #[tokio::test]
+    async fn test_model_selection() {
+        let transport = Arc::new(InMemoryTransport::new(|_| ok(r#"{"id":"op1","done":false}"#)));
+        let art = Art::new("key", "folder".to_string())
+            .with_transport(transport.clone())
+            .with_default_model(Version::RC);
+
+        art.generate_image(request()).await.unwrap();
+        let mut pinned = request();
+        pinned.model = Some(ArtModel::new(ModelType::YandexArt, Version::Custom("2024-05".to_string())));
+        art.generate_image(pinned).await.unwrap();
+
+        let model_uris: Vec<String> = transport
+            .requests()
+            .iter()
+            .map(|r| serde_json::from_slice::<serde_json::Value>(r.body.as_deref().unwrap()).unwrap()["modelUri"].to_string())
+            .collect();
+        assert_eq!(model_uris, ["\"art://folder/yandex-art/rc\"", "\"art://folder/yandex-art/2024-05\""]);
+    }
+
+    #[tokio::test]
+    async fn test_operation_error() {
+        let transport = InMemoryTransport::from_responses([ok(
+            r#"{"id":"op1","done":true,"error":{"code":3,"message":"bad prompt","details":[
+                {"@type":"type.googleapis.com/google.rpc.BadRequest","fieldViolations":[{"field":"messages[0].text","description":"too long"}]}
+            ]}}"#,
+        )]);
+        let art = Art::new("key", "folder".to_string()).with_transport(transport);
+
+        let error = art.check_operation("op1").await.unwrap_err();
+        assert_eq!(error.to_string(), "API error: bad prompt");
+        let ArtError::Operation(error) = error else { panic!("unexpected error: {error:?}") };
+        assert_eq!(error.code, 3);
+        assert_eq!(error.field_violations().map(|v| v.field.as_str()).collect::<Vec<_>>(), ["messages[0].text"]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_variations() {
+        let transport = Arc::new(InMemoryTransport::new(|request| {
+            if request.method == Method::POST {
+                let body: serde_json::Value = serde_json::from_slice(request.body.as_deref().unwrap()).unwrap();
+                let seed = body["generationOptions"]["seed"].as_i64().unwrap();
+                if seed == 2 {
+                    return ok(r#"{"id":"op2","done":true,"error":{"code":8,"message":"quota exceeded"}}"#);
+                }
+                return ok(&format!(r#"{{"id":"op{seed}","done":false}}"#));
+            }
+            let id = request.url.rsplit('/').next().unwrap();
+            ok(&format!(r#"{{"id":"{id}","done":true,"response":{{"image":"{id}","modelVersion":"1"}}}}"#))
+        }));
+        let art = Art::new("key", "folder".to_string()).with_transport(transport.clone());
+
+        let options = VariationOptions::new().with_poll_interval(Duration::ZERO);
+        let results = art.generate_variations(request(), vec![1, 2, 3], options).await.unwrap();
+
+        assert_eq!(results.len(), 3);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!((first.seed, first.image.as_str(), first.model_version.as_deref()), (1, "op1", Some("1")));
+        assert_eq!(first.request.seed(), Some(1));
+        let error = results[1].as_ref().unwrap_err();
+        assert_eq!(error.seed, 2);
+        assert_eq!(error.to_string(), "Seed 2: API error: quota exceeded");
+        assert_eq!(results[2].as_ref().unwrap().operation_id, "op3");
+        assert_eq!(transport.requests().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_generate_variations_concurrency() {
+        let transport = Arc::new(InMemoryTransport::new(|request| {
+            let id = request.url.rsplit('/').next().unwrap();
+            match request.method {
+                Method::POST => ok(r#"{"id":"op","done":false}"#),
+                _ => ok(&format!(r#"{{"id":"{id}","done":true,"response":{{"image":"img"}}}}"#)),
+            }
+        }));
+        let art = Art::new("key", "folder".to_string()).with_transport(transport.clone());
+
+        let options = VariationOptions::new().with_poll_interval(Duration::ZERO).with_concurrency(1);
+        let results = art.generate_variations(request(), 0..3, options).await.unwrap();
+
+        assert_eq!(results.iter().map(|r| r.as_ref().unwrap().seed).collect::<Vec<_>>(), [0, 1, 2]);
+        let methods: Vec<Method> = transport.requests().into_iter().map(|r| r.method).collect();
+        assert_eq!(methods, [Method::POST, Method::GET, Method::POST, Method::GET, Method::POST, Method::GET]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_variations_reserves_budget() {
+        use nn_yandex_core::usage::{Budget, PriceTable, UsageTracker};
+
+        let transport = Arc::new(InMemoryTransport::new(|request| {
+            let body: serde_json::Value = serde_json::from_slice(request.body.as_deref().unwrap()).unwrap();
+            match body["generationOptions"]["seed"].as_i64() {
+                Some(2) => Ok(HttpResponse::new(StatusCode::BAD_REQUEST, "bad")),
+                _ => ok(r#"{"id":"op","done":true,"response":{"image":"img"}}"#),
+            }
+        }));
+        let tracker = Arc::new(UsageTracker::new(PriceTable::new(0.0, 1.0)).with_budget(Budget { soft: None, hard: Some(3.0) }));
+        let art = Art::new("key", "folder".to_string()).with_transport(transport.clone()).with_usage_tracker(tracker.clone());
+
+        let error = art.generate_variations(request(), 1..4, VariationOptions::new()).await.unwrap_err();
+        assert!(matches!(error, ArtError::Budget(_)));
+        assert!(transport.requests().is_empty());
+
+        let results = art.generate_variations(request(), 1..3, VariationOptions::new()).await.unwrap();
+        assert!(results[0].is_ok() && results[1].is_err());
+        assert!((tracker.spent() - 1.0).abs() < 1e-9);
+
+        let error = art.generate_variations(request(), SeedStrategy::Random(usize::MAX), VariationOptions::new()).await;
+        assert!(matches!(error, Err(ArtError::Build(crate::error::BuildError::TooManySeeds { .. }))));
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_operation_id_is_validated() {
+        let transport = Arc::new(InMemoryTransport::new(|_| ok(r#"{"id":"op-1_a","done":false}"#)));
+        let art = Art::new("key", "folder".to_string()).with_transport(transport.clone());
+
+        for id in ["", "op1/../other", "op1?x=1", "op 1"] {
+            let error = art.check_operation(id).await.unwrap_err();
+            assert!(matches!(error, ArtError::Build(BuildError::InvalidOperationId(ref bad)) if bad == id));
+            assert!(matches!(art.cancel_operation(id).await, Err(ArtError::Build(_))));
+        }
+        assert!(transport.requests().is_empty());
+
+        art.cancel_operation("op-1_a").await.unwrap();
+        assert_eq!(transport.requests()[0].url, format!("{YANDEX_GET_OPERATION}/op-1_a:cancel"));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_operation_timeout() {
+        let transport = Arc::new(InMemoryTransport::new(|_| ok(r#"{"id":"op1","done":false}"#)));
+        let art = Art::new("key", "folder".to_string()).with_transport(transport.clone());
+
+        let error = art.wait_for_operation("op1", Duration::ZERO, Duration::ZERO).await.unwrap_err();
+
+        assert!(matches!(error, ArtError::Timeout(ref id) if id == "op1"));
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_statuses() {
+        let transport = Arc::new(InMemoryTransport::from_responses([
+            Ok(HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, "upstream unavailable")),
+            ok(r#"{"id":"op1","done":false}"#),
+        ]));
+        let art = Art::new("key", "folder".to_string())
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy::new().with_initial_backoff(Duration::ZERO));
+
+        assert_eq!(art.generate_image(request()).await.unwrap().id, "op1");
+        assert_eq!(transport.requests().len(), 2);
+
+        let transport = InMemoryTransport::from_responses([Ok(HttpResponse::new(StatusCode::BAD_REQUEST, "bad"))]);
+        let art = art.with_transport(transport);
+        let error = art.generate_image(request()).await.unwrap_err();
+        assert!(matches!(error, ArtError::Status(StatusCode::BAD_REQUEST)));
+        assert!(!error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_request_has_field_violations() {
+        let body = r#"{"code":3,"message":"invalid request","details":[{"@type":"type.googleapis.com/google.rpc.BadRequest","fieldViolations":[{"field":"messages[0].text","description":"too long"}]}]}"#;
+        let transport = InMemoryTransport::from_responses([Ok(HttpResponse::new(StatusCode::BAD_REQUEST, body))]);
+        let art = Art::new("key", "folder".to_string()).with_transport(transport);
+
+        let error = art.generate_image(request()).await.unwrap_err();
+        let ArtError::Rejected { status, error: ref rejected } = error else {
+            panic!("expected Rejected, got {error:?}");
+        };
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(rejected.field_violations().map(|v| v.field.as_str()).collect::<Vec<_>>(), ["messages[0].text"]);
+        assert!(!error.is_retryable());
     }
 }
@@ -1,2 +1,3 @@
+pub mod model_type;
 pub mod response;
 pub mod request;
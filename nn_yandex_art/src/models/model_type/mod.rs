@@ -0,0 +1,231 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::BuildError;
+
+/// Supported image models
+///
+/// Parses from, displays and serializes as the model URI segment (`yandex-art`);
+/// unknown names parse into `Custom`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ModelType {
+    YandexArt,
+    /// Any other model name, sent as-is
+    Custom(String),
+}
+
+impl ModelType {
+    /// Returns the model URI segment as string
+    pub fn as_str(&self) -> &str {
+        match self {
+            ModelType::YandexArt => "yandex-art",
+            ModelType::Custom(name) => name,
+        }
+    }
+}
+
+impl FromStr for ModelType {
+    type Err = BuildError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "yandex-art" => ModelType::YandexArt,
+            _ => ModelType::Custom(validate_segment(s)?.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ModelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Supported versions
+///
+/// Parses from, displays and serializes as the version URI segment (`latest`, `rc`, ...);
+/// unknown versions parse into `Custom`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Version {
+    Deprecated,
+    Latest,
+    RC,
+    /// Any other version string, e.g. a pinned version for reproducible results
+    Custom(String),
+}
+
+impl Version {
+    /// Returns the version as string
+    pub fn as_str(&self) -> &str {
+        match self {
+            Version::Deprecated => "deprecated",
+            Version::Latest => "latest",
+            Version::RC => "rc",
+            Version::Custom(version) => version,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = BuildError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "deprecated" => Version::Deprecated,
+            "latest" => Version::Latest,
+            "rc" => Version::RC,
+            _ => Version::Custom(validate_segment(s)?.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Image model and version a request is sent to
+///
+/// Parses from, displays and serializes as `name/version`; a bare `name` means its latest version.
+/// The default is `yandex-art/latest`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ArtModel {
+    pub model_type: ModelType,
+    pub version: Version,
+}
+
+impl ArtModel {
+    pub fn new(model_type: ModelType, version: Version) -> Self {
+        ArtModel { model_type, version }
+    }
+
+    /// Returns the URI sent to the API, `art://<folder>/<name>/<version>`
+    pub fn resolve(&self, folder_id: &str) -> String {
+        format!("art://{}/{}/{}", folder_id, self.model_type, self.version)
+    }
+}
+
+impl Default for ArtModel {
+    fn default() -> Self {
+        ArtModel::new(ModelType::YandexArt, Version::Latest)
+    }
+}
+
+impl From<(ModelType, Version)> for ArtModel {
+    fn from((model_type, version): (ModelType, Version)) -> Self {
+        ArtModel::new(model_type, version)
+    }
+}
+
+impl From<Version> for ArtModel {
+    fn from(version: Version) -> Self {
+        ArtModel::new(ModelType::YandexArt, version)
+    }
+}
+
+impl FromStr for ArtModel {
+    type Err = BuildError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((name, version)) => Ok(ArtModel::new(name.parse()?, version.parse()?)),
+            None => Ok(ArtModel::new(s.parse()?, Version::Latest)),
+        }
+    }
+}
+
+impl fmt::Display for ArtModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.model_type, self.version)
+    }
+}
+
+macro_rules! impl_serde_as_str {
+    ($ty:ty) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_serde_as_str!(ModelType);
+impl_serde_as_str!(Version);
+impl_serde_as_str!(ArtModel);
+
+fn validate_segment(segment: &str) -> Result<&str, BuildError> {
+    if segment.is_empty() || segment.contains('/') || segment.chars().any(char::is_whitespace) {
+        return Err(BuildError::InvalidModel(segment.to_string()));
+    }
+    Ok(segment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let model: ArtModel = "yandex-art/rc".parse().unwrap();
+        assert_eq!(model, ArtModel::from(Version::RC));
+        assert_eq!(model.to_string(), "yandex-art/rc");
+
+        let model: ArtModel = "yandex-art-2".parse().unwrap();
+        assert_eq!(model, ArtModel::new(ModelType::Custom("yandex-art-2".to_string()), Version::Latest));
+        assert_eq!("yandex-art/2024-05".parse::<ArtModel>().unwrap().version, Version::Custom("2024-05".to_string()));
+
+        assert_eq!("yandex art".parse::<ArtModel>(), Err(BuildError::InvalidModel("yandex art".to_string())));
+        assert!("yandex-art/".parse::<ArtModel>().is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for model in [ModelType::YandexArt, ModelType::Custom("yandex-art-2".to_string())] {
+            assert_eq!(model.to_string().parse::<ModelType>().unwrap(), model);
+        }
+        for version in [Version::Deprecated, Version::Latest, Version::RC, Version::Custom("2024-05".to_string())] {
+            assert_eq!(version.to_string().parse::<Version>().unwrap(), version);
+        }
+        let model = ArtModel::from(Version::RC);
+        assert_eq!(model.to_string().parse::<ArtModel>().unwrap(), model);
+    }
+
+    #[test]
+    fn test_serde() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Defaults {
+            model: ModelType,
+            version: Version,
+            art_model: ArtModel,
+        }
+
+        let defaults: Defaults =
+            serde_json::from_str(r#"{"model": "yandex-art", "version": "rc", "art_model": "yandex-art"}"#).unwrap();
+        assert_eq!(defaults, Defaults { model: ModelType::YandexArt, version: Version::RC, art_model: ArtModel::default() });
+        assert_eq!(
+            serde_json::to_string(&defaults).unwrap(),
+            r#"{"model":"yandex-art","version":"rc","art_model":"yandex-art/latest"}"#
+        );
+        assert!(serde_json::from_str::<ArtModel>(r#""yandex art""#).is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(ArtModel::default().resolve("folder"), "art://folder/yandex-art/latest");
+        assert_eq!(
+            ArtModel::from((ModelType::YandexArt, Version::Deprecated)).resolve("folder"),
+            "art://folder/yandex-art/deprecated"
+        );
+    }
+}
@@ -1,12 +1,133 @@
 use serde::Serialize;
+use crate::error::BuildError;
 
-#[derive(Serialize, Clone, Debug)]
+/// Widest supported ratio of the longer side to the shorter one (4:1 and 1:4)
+pub const MAX_RATIO: f64 = 4.0;
+
+/// Approximate number of pixels in a generated image
+const OUTPUT_PIXELS: f64 = 1024.0 * 1024.0;
+
+/// Generated image sides are multiples of this
+const OUTPUT_STEP: u32 = 64;
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct AspectRatio{
     width_ratio: i64,
     height_ratio: i64,
 }
 
+/// Common aspect ratios
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AspectRatioPreset {
+    /// 1:1
+    Square,
+    /// 16:9
+    Widescreen,
+    /// 9:16
+    Vertical,
+    /// 4:3
+    Standard,
+    /// 3:2
+    Photo,
+    /// 3:1, e.g. page headers
+    Banner,
+    /// 9:16, e.g. phone stories
+    Story,
+}
+
+impl AspectRatioPreset {
+    /// Returns `(width, height)` of the preset
+    pub fn ratio(self) -> (i64, i64) {
+        match self {
+            AspectRatioPreset::Square => (1, 1),
+            AspectRatioPreset::Widescreen => (16, 9),
+            AspectRatioPreset::Vertical | AspectRatioPreset::Story => (9, 16),
+            AspectRatioPreset::Standard => (4, 3),
+            AspectRatioPreset::Photo => (3, 2),
+            AspectRatioPreset::Banner => (3, 1),
+        }
+    }
+}
+
+impl std::str::FromStr for AspectRatioPreset {
+    type Err = BuildError;
+
+    /// Parses a preset name (`square`, `banner`, ...) or its ratio (`16:9`, ...)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "square" | "1:1" => Ok(AspectRatioPreset::Square),
+            "widescreen" | "16:9" => Ok(AspectRatioPreset::Widescreen),
+            "vertical" | "9:16" => Ok(AspectRatioPreset::Vertical),
+            "standard" | "4:3" => Ok(AspectRatioPreset::Standard),
+            "photo" | "3:2" => Ok(AspectRatioPreset::Photo),
+            "banner" | "3:1" => Ok(AspectRatioPreset::Banner),
+            "story" => Ok(AspectRatioPreset::Story),
+            other => Err(BuildError::UnknownPreset(other.to_string())),
+        }
+    }
+}
+
+impl From<AspectRatioPreset> for AspectRatio {
+    fn from(preset: AspectRatioPreset) -> Self {
+        let (width_ratio, height_ratio) = preset.ratio();
+        AspectRatio { width_ratio, height_ratio }
+    }
+}
+
+impl AspectRatio {
+    /// Aspect ratio of an image with the given size in pixels
+    ///
+    /// The ratio is reduced by the greatest common divisor (1920x1080 becomes 16:9) and
+    /// clamped to the supported range (1000x100 becomes 4:1).
+    ///
+    /// # Arguments
+    /// * `width`, `height` - target size in pixels; must not be zero
+    pub fn from_dimensions(width: u32, height: u32) -> Result<Self, BuildError> {
+        if width == 0 || height == 0 {
+            return Err(BuildError::NonPositiveAspectRatio { width: width as i64, height: height as i64 });
+        }
+        let divisor = gcd(width as i64, height as i64);
+        let (width_ratio, height_ratio) = (width as i64 / divisor, height as i64 / divisor);
+        let ratio = width_ratio as f64 / height_ratio as f64;
+
+        let max = MAX_RATIO as i64;
+        Ok(if ratio > MAX_RATIO {
+            AspectRatio { width_ratio: max, height_ratio: 1 }
+        } else if ratio < 1.0 / MAX_RATIO {
+            AspectRatio { width_ratio: 1, height_ratio: max }
+        } else {
+            AspectRatio { width_ratio, height_ratio }
+        })
+    }
+
+    pub fn width_ratio(&self) -> i64 {
+        self.width_ratio
+    }
+
+    pub fn height_ratio(&self) -> i64 {
+        self.height_ratio
+    }
+
+    /// Expected size of generated images in pixels
+    ///
+    /// # Returns
+    /// `(width, height)` of about one megapixel with this ratio, with sides rounded to a
+    /// multiple of 64 (1024x1024 for 1:1, 1344x768 for 16:9)
+    pub fn resolution(&self) -> (u32, u32) {
+        let ratio = self.width_ratio as f64 / self.height_ratio as f64;
+        let round = |side: f64| ((side / OUTPUT_STEP as f64).round() as u32).max(1) * OUTPUT_STEP;
+        (round((OUTPUT_PIXELS * ratio).sqrt()), round((OUTPUT_PIXELS / ratio).sqrt()))
+    }
+}
+
+fn gcd(mut a: i64, mut b: i64) -> i64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 /// Builder for AspectRatio
 #[derive(Debug)]
 pub struct AspectRatioBuilder{
@@ -14,7 +135,14 @@ pub struct AspectRatioBuilder{
     height_ratio: i64,
 }
 
+impl Default for AspectRatioBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Builder for AspectRatio
+/// Both ratios must be positive and within `MAX_RATIO` of each other
 impl AspectRatioBuilder{
     pub fn new() -> Self{
         AspectRatioBuilder{
@@ -33,11 +161,24 @@ impl AspectRatioBuilder{
         self
     }
 
-    pub fn build(self) -> AspectRatio{
-        AspectRatio{
+    /// Sets both ratios from a preset
+    pub fn preset(mut self, preset: AspectRatioPreset) -> Self {
+        (self.width_ratio, self.height_ratio) = preset.ratio();
+        self
+    }
+
+    pub fn build(self) -> Result<AspectRatio, BuildError> {
+        if self.width_ratio <= 0 || self.height_ratio <= 0 {
+            return Err(BuildError::NonPositiveAspectRatio { width: self.width_ratio, height: self.height_ratio });
+        }
+        let ratio = self.width_ratio as f64 / self.height_ratio as f64;
+        if !(1.0 / MAX_RATIO..=MAX_RATIO).contains(&ratio) {
+            return Err(BuildError::AspectRatioOutOfRange { width: self.width_ratio, height: self.height_ratio });
+        }
+        Ok(AspectRatio{
             width_ratio: self.width_ratio,
             height_ratio: self.height_ratio
-        }
+        })
     }
 }
 
@@ -47,7 +188,7 @@ mod tests {
 
     #[test]
     fn test_aspect_ratio_builder_defaults() {
-        let ar = AspectRatioBuilder::new().build();
+        let ar = AspectRatioBuilder::new().build().unwrap();
         assert_eq!(ar.width_ratio, 1);
         assert_eq!(ar.height_ratio, 1);
     }
@@ -57,9 +198,49 @@ mod tests {
         let ar = AspectRatioBuilder::new()
             .width_ratio(16)
             .height_ratio(9)
-            .build();
+            .build()
+            .unwrap();
 
         assert_eq!(ar.width_ratio, 16);
         assert_eq!(ar.height_ratio, 9);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_aspect_ratio_builder_validation() {
+        let zero = AspectRatioBuilder::new().width_ratio(0).build();
+        assert_eq!(zero.unwrap_err(), BuildError::NonPositiveAspectRatio { width: 0, height: 1 });
+        assert!(AspectRatioBuilder::new().height_ratio(-1).build().is_err());
+        assert_eq!(
+            AspectRatioBuilder::new().width_ratio(5).build().unwrap_err(),
+            BuildError::AspectRatioOutOfRange { width: 5, height: 1 }
+        );
+        assert!(AspectRatioBuilder::new().width_ratio(4).build().is_ok());
+    }
+
+    #[test]
+    fn test_presets() {
+        let ar = AspectRatioBuilder::new().preset(AspectRatioPreset::Widescreen).build().unwrap();
+        assert_eq!(ar, AspectRatio::from(AspectRatioPreset::Widescreen));
+        assert_eq!("story".parse::<AspectRatioPreset>().unwrap().ratio(), (9, 16));
+        assert_eq!("4:3".parse::<AspectRatioPreset>().unwrap(), AspectRatioPreset::Standard);
+        assert_eq!("panorama".parse::<AspectRatioPreset>(), Err(BuildError::UnknownPreset("panorama".to_string())));
+    }
+
+    #[test]
+    fn test_from_dimensions() {
+        let ar = AspectRatio::from_dimensions(1920, 1080).unwrap();
+        assert_eq!((ar.width_ratio(), ar.height_ratio()), (16, 9));
+        let ar = AspectRatio::from_dimensions(1000, 100).unwrap();
+        assert_eq!((ar.width_ratio(), ar.height_ratio()), (4, 1));
+        let ar = AspectRatio::from_dimensions(100, 1000).unwrap();
+        assert_eq!((ar.width_ratio(), ar.height_ratio()), (1, 4));
+        assert!(AspectRatio::from_dimensions(0, 10).is_err());
+    }
+
+    #[test]
+    fn test_resolution() {
+        assert_eq!(AspectRatio::from(AspectRatioPreset::Square).resolution(), (1024, 1024));
+        assert_eq!(AspectRatio::from(AspectRatioPreset::Widescreen).resolution(), (1344, 768));
+        assert_eq!(AspectRatio::from(AspectRatioPreset::Vertical).resolution(), (768, 1344));
+    }
+}
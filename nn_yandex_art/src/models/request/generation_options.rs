@@ -8,10 +8,17 @@ use crate::models::request::types::ImageType;
 pub struct GenerationOptions{
     mime_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    seed: Option<i64>,
+    pub(crate) seed: Option<i64>,
     aspect_ratio: AspectRatio,
 }
 
+impl GenerationOptions {
+    /// Returns the seed, `None` when the API picks a random one
+    pub fn seed(&self) -> Option<i64> {
+        self.seed
+    }
+}
+
 /// Builder for GenerationOptions
 /// `mime_type` & `aspect_ratio` are required
 #[derive(Debug)]
@@ -22,6 +29,12 @@ pub struct GenerationOptionsBuilder {
 }
 
 
+impl Default for GenerationOptionsBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Builder for GenerationOptions
 /// `mime_type` & `aspect_ratio` are required
 impl GenerationOptionsBuilder {
@@ -50,11 +63,11 @@ impl GenerationOptionsBuilder {
     
     pub fn build(self) -> Result<GenerationOptions, BuildError> {
         if self.mime_type.is_none() {
-            return Err(BuildError::new("Mime type is required"));
+            return Err(BuildError::MissingMimeType);
         }
         
         if self.aspect_ratio.is_none() {
-            return Err(BuildError::new("Aspect ratio is required"));
+            return Err(BuildError::MissingAspectRatio);
         }
         
         Ok(GenerationOptions {
@@ -72,7 +85,7 @@ mod tests {
 
     #[test]
     fn test_generation_options_builder_success() {
-        let ar = AspectRatioBuilder::new().build();
+        let ar = AspectRatioBuilder::new().build().unwrap();
 
         let options = GenerationOptionsBuilder::new()
             .mime_type(ImageType::Png)
@@ -87,7 +100,7 @@ mod tests {
 
     #[test]
     fn test_generation_options_builder_missing_mime_type() {
-        let ar = AspectRatioBuilder::new().build();
+        let ar = AspectRatioBuilder::new().build().unwrap();
         let result = GenerationOptionsBuilder::new()
             .aspect_ratio(ar)
             .build();
@@ -8,6 +8,21 @@ pub struct Message{
     weight: i64,
 }
 
+impl Message {
+    /// Message from text known to pass the builder checks
+    pub(crate) fn new_unchecked(text: &str, weight: i64) -> Self {
+        Message { text: text.to_string(), weight }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn weight(&self) -> i64 {
+        self.weight
+    }
+}
+
 /// Builder for Message
 /// `text` is required
 #[derive(Debug)]
@@ -17,8 +32,15 @@ pub struct MessageBuilder {
 }
 
 
+impl Default for MessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Builder for Message
-/// `text` is required
+/// `text` is required; negative weights steer away from the text. Prompt length and weight
+/// limits are left to the API, which reports them as field violations in `ArtError::Rejected`
 impl MessageBuilder {
     pub fn new() -> Self{
         MessageBuilder{
@@ -39,7 +61,7 @@ impl MessageBuilder {
     
     pub fn build(self) -> Result<Message, BuildError> {
         if self.text.is_empty() {
-            return Err(BuildError::new("Text is required"))
+            return Err(BuildError::MissingText)
         }
         Ok(Message{
             text: self.text,
@@ -1,6 +1,7 @@
 pub mod message;
 pub mod aspect_ratio;
 pub mod generation_options;
+pub mod prompt;
 pub mod types;
 
 use message::Message;
@@ -8,22 +9,50 @@ use generation_options::GenerationOptions;
 
 use serde::Serialize;
 use crate::error::BuildError;
+use crate::models::model_type::ArtModel;
+
+/// Most messages the API accepts in one request
+pub const MAX_MESSAGES: usize = 10;
 
 #[derive(Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Request{
     pub(crate) model_uri: String,
-    messages: Vec<Message>,
+    pub(crate) messages: Vec<Message>,
     generation_options: GenerationOptions,
+    /// Model chosen for this request; the client default is used when not set
+    #[serde(skip)]
+    pub(crate) model: Option<ArtModel>,
+}
+
+impl Request {
+    /// Returns the seed of the generation options
+    pub fn seed(&self) -> Option<i64> {
+        self.generation_options.seed()
+    }
+
+    /// Returns the request with its seed replaced; the same seed, prompt and model version
+    /// produce the same image
+    pub fn with_seed(mut self, seed: i64) -> Self {
+        self.generation_options.seed = Some(seed);
+        self
+    }
 }
 
 
 /// Builder for Request
-/// `messages` & `generation_options` are required
+/// `messages` & `generation_options` are required; at most `MAX_MESSAGES` messages
 #[derive(Debug)]
 pub struct RequestBuilder {
     messages: Vec<Message>,
     generation_options: Option<GenerationOptions>,
+    model: Option<ArtModel>,
+}
+
+impl Default for RequestBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RequestBuilder {
@@ -31,6 +60,7 @@ impl RequestBuilder {
         Self {
             messages: Vec::new(),
             generation_options: None,
+            model: None,
         }
     }
 
@@ -39,25 +69,42 @@ impl RequestBuilder {
         self
     }
 
+    /// Adds several messages, e.g. from a parsed `WeightedPrompt`
+    pub fn messages(mut self, messages: impl IntoIterator<Item = Message>) -> Self {
+        self.messages.extend(messages);
+        self
+    }
+
     pub fn generation_options(mut self, generation_options: GenerationOptions) -> Self {
         self.generation_options = Some(generation_options);
         self
     }
 
+    /// Sends the request to `model` instead of the client's default model
+    pub fn model(mut self, model: impl Into<ArtModel>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
     pub fn build(self) -> Result<Request, BuildError> {
 
         if self.messages.is_empty() {
-            return Err(BuildError::new("No messages added"));
+            return Err(BuildError::NoMessages);
+        }
+
+        if self.messages.len() > MAX_MESSAGES {
+            return Err(BuildError::TooManyMessages { count: self.messages.len(), max: MAX_MESSAGES });
         }
 
         if self.generation_options.is_none() {
-            return Err(BuildError::new("No generation options added"));
+            return Err(BuildError::MissingGenerationOptions);
         }
 
         Ok(Request {
             model_uri: "".to_string(),
             messages: self.messages,
             generation_options: self.generation_options.unwrap(),
+            model: self.model,
         })
     }
 }
@@ -81,7 +128,8 @@ mod tests {
         let aspect_ratio = AspectRatioBuilder::new()
             .width_ratio(1)
             .height_ratio(1)
-            .build();
+            .build()
+            .unwrap();
 
         let options = GenerationOptionsBuilder::new()
             .mime_type(ImageType::Png)
@@ -103,7 +151,8 @@ mod tests {
         let aspect_ratio = AspectRatioBuilder::new()
             .width_ratio(1)
             .height_ratio(1)
-            .build();
+            .build()
+            .unwrap();
 
         let options = GenerationOptionsBuilder::new()
             .mime_type(ImageType::Jpeg)
@@ -134,4 +183,12 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err().to_string(), "No generation options added");
     }
+
+    #[test]
+    fn test_request_builder_limits() {
+        let message = |text: &str| MessageBuilder::new().text(text).build().unwrap();
+
+        let result = (0..=MAX_MESSAGES).fold(RequestBuilder::new(), |b, _| b.message(message("fox"))).build();
+        assert_eq!(result.unwrap_err(), BuildError::TooManyMessages { count: 11, max: MAX_MESSAGES });
+    }
 }
@@ -0,0 +1,252 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::BuildError;
+use crate::models::request::message::{Message, MessageBuilder};
+
+const DEFAULT_WEIGHT: i64 = 1;
+
+/// Weighted prompt in a compact syntax
+///
+/// Messages are separated by `|`; a trailing ` :N` sets the weight of a message (1 when
+/// omitted): `"red fox in snow :2 | watercolor | blurry :-1"`. `\|` and `\\` escape a pipe
+/// and a backslash. A colon not preceded by whitespace is part of the text (`"poster 16:9"`).
+///
+/// `Display` produces the same syntax, so formatting and parsing again gives the same messages.
+#[derive(Clone, Debug, Default)]
+pub struct WeightedPrompt {
+    messages: Vec<Message>,
+}
+
+impl WeightedPrompt {
+    pub fn new() -> Self {
+        WeightedPrompt { messages: Vec::new() }
+    }
+
+    /// Appends a message
+    pub fn message(mut self, message: Message) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Appends the weighted messages of a style
+    pub fn style(mut self, style: Style) -> Self {
+        self.messages.extend(style.messages());
+        self
+    }
+
+    pub fn messages(&self) -> &[Message] {
+        &self.messages
+    }
+
+    pub fn into_messages(self) -> Vec<Message> {
+        self.messages
+    }
+}
+
+impl From<WeightedPrompt> for Vec<Message> {
+    fn from(prompt: WeightedPrompt) -> Self {
+        prompt.messages
+    }
+}
+
+impl IntoIterator for WeightedPrompt {
+    type Item = Message;
+    type IntoIter = std::vec::IntoIter<Message>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.messages.into_iter()
+    }
+}
+
+impl FromStr for WeightedPrompt {
+    type Err = BuildError;
+
+    /// Parses the compact syntax; every message is validated by `MessageBuilder`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let messages = split_unescaped(s)
+            .iter()
+            .map(|segment| {
+                let (text, weight) = split_weight(segment.trim());
+                MessageBuilder::new().text(text).weight(weight.unwrap_or(DEFAULT_WEIGHT)).build()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(WeightedPrompt { messages })
+    }
+}
+
+impl fmt::Display for WeightedPrompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, message) in self.messages.iter().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            let text = message.text().replace('\\', "\\\\").replace('|', "\\|");
+            // The weight is written when the text would otherwise be read as weighted
+            if message.weight() != DEFAULT_WEIGHT || split_weight(&text).1.is_some() {
+                write!(f, "{} :{}", text, message.weight())?;
+            } else {
+                write!(f, "{}", text)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits on `|` not preceded by a backslash and removes the escapes
+fn split_unescaped(s: &str) -> Vec<String> {
+    let mut segments = vec![String::new()];
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(next @ ('|' | '\\')) => segments.last_mut().unwrap().push(next),
+                Some(next) => {
+                    let segment = segments.last_mut().unwrap();
+                    segment.push('\\');
+                    segment.push(next);
+                }
+                None => segments.last_mut().unwrap().push('\\'),
+            },
+            '|' => segments.push(String::new()),
+            c => segments.last_mut().unwrap().push(c),
+        }
+    }
+    segments
+}
+
+/// Splits `"text :N"` into the text and the weight
+fn split_weight(segment: &str) -> (&str, Option<i64>) {
+    if let Some((text, weight)) = segment.rsplit_once(':')
+        && (text.is_empty() || text.ends_with(char::is_whitespace))
+        && let Ok(weight) = weight.trim().parse()
+    {
+        return (text.trim_end(), Some(weight));
+    }
+    (segment, None)
+}
+
+/// Named image styles that add weighted style messages to a prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Photo,
+    Watercolor,
+    OilPainting,
+    Anime,
+    PixelArt,
+    Sketch,
+    Cinematic,
+    Flat,
+}
+
+impl Style {
+    pub const ALL: [Style; 8] = [
+        Style::Photo,
+        Style::Watercolor,
+        Style::OilPainting,
+        Style::Anime,
+        Style::PixelArt,
+        Style::Sketch,
+        Style::Cinematic,
+        Style::Flat,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Style::Photo => "photo",
+            Style::Watercolor => "watercolor",
+            Style::OilPainting => "oil-painting",
+            Style::Anime => "anime",
+            Style::PixelArt => "pixel-art",
+            Style::Sketch => "sketch",
+            Style::Cinematic => "cinematic",
+            Style::Flat => "flat",
+        }
+    }
+
+    /// Returns the style messages as `(text, weight)`; negative weights steer away
+    pub fn terms(self) -> &'static [(&'static str, i64)] {
+        match self {
+            Style::Photo => &[("professional photo, natural light, high detail", 2), ("drawing, illustration", -1)],
+            Style::Watercolor => &[("watercolor painting, soft washes, paper texture", 2), ("photo, sharp edges", -1)],
+            Style::OilPainting => &[("oil painting, visible brush strokes, canvas texture", 2), ("photo", -1)],
+            Style::Anime => &[("anime style, cel shading, clean line art", 2), ("photorealistic", -1)],
+            Style::PixelArt => &[("pixel art, 16-bit, limited palette", 2), ("smooth gradients, blur", -1)],
+            Style::Sketch => &[("pencil sketch, hatching, monochrome", 2), ("color", -1)],
+            Style::Cinematic => &[("cinematic still, dramatic lighting, shallow depth of field", 2), ("flat lighting", -1)],
+            Style::Flat => &[("flat vector illustration, simple shapes, solid colors", 2), ("photo, texture, gradients", -1)],
+        }
+    }
+
+    /// Returns the style messages
+    pub fn messages(self) -> Vec<Message> {
+        self.terms()
+            .iter()
+            .map(|(text, weight)| Message::new_unchecked(text, *weight))
+            .collect()
+    }
+}
+
+impl FromStr for Style {
+    type Err = BuildError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase().replace('_', "-");
+        Style::ALL
+            .into_iter()
+            .find(|style| style.name() == name)
+            .ok_or_else(|| BuildError::UnknownStyle(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(prompt: &WeightedPrompt) -> Vec<(&str, i64)> {
+        prompt.messages().iter().map(|m| (m.text(), m.weight())).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let prompt: WeightedPrompt = "red fox in snow :2 | watercolor :1 | blurry :-1".parse().unwrap();
+        assert_eq!(parts(&prompt), vec![("red fox in snow", 2), ("watercolor", 1), ("blurry", -1)]);
+
+        let prompt: WeightedPrompt = r"poster 16:9 | a \| b | c:3".parse().unwrap();
+        assert_eq!(parts(&prompt), vec![("poster 16:9", 1), ("a | b", 1), ("c:3", 1)]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("fox | :2".parse::<WeightedPrompt>().unwrap_err(), BuildError::MissingText);
+        // Weight limits are checked by the API, not by the parser
+        assert_eq!(parts(&"fox :99".parse().unwrap()), vec![("fox", 99)]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for input in ["red fox in snow :2 | watercolor | blurry :-1", r"a \| b \\ c | d :5 :1", "x :3"] {
+            let prompt: WeightedPrompt = input.parse().unwrap();
+            assert_eq!(prompt.to_string(), input);
+            let again: WeightedPrompt = prompt.to_string().parse().unwrap();
+            assert_eq!(parts(&again), parts(&prompt));
+        }
+    }
+
+    #[test]
+    fn test_styles() {
+        let prompt = "red fox".parse::<WeightedPrompt>().unwrap().style("watercolor".parse().unwrap());
+        assert_eq!(
+            parts(&prompt),
+            vec![("red fox", 1), ("watercolor painting, soft washes, paper texture", 2), ("photo, sharp edges", -1)]
+        );
+        for style in Style::ALL {
+            assert_eq!(style.name().parse::<Style>().unwrap(), style);
+            for (text, weight) in style.terms() {
+                assert!(MessageBuilder::new().text(text).weight(*weight).build().is_ok());
+            }
+        }
+        assert_eq!(crate::models::request::RequestBuilder::new().messages(prompt).build().unwrap_err(), BuildError::MissingGenerationOptions);
+        assert_eq!("vaporwave".parse::<Style>().unwrap_err(), BuildError::UnknownStyle("vaporwave".to_string()));
+    }
+}
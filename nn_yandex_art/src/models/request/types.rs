@@ -5,12 +5,11 @@ pub enum ImageType{
     Png,
 }
 
-impl ImageType {
-    pub fn to_string(&self) -> String {
-        let prefix = "image/".to_string();
+impl std::fmt::Display for ImageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ImageType::Jpeg => prefix + "jpeg",
-            ImageType::Png => prefix + "png",
+            ImageType::Jpeg => write!(f, "image/jpeg"),
+            ImageType::Png => write!(f, "image/png"),
         }
     }
 }
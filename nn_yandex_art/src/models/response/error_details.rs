@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Map, Value};
+
+const BAD_REQUEST: &str = "type.googleapis.com/google.rpc.BadRequest";
+const QUOTA_FAILURE: &str = "type.googleapis.com/google.rpc.QuotaFailure";
+const RETRY_INFO: &str = "type.googleapis.com/google.rpc.RetryInfo";
+const ERROR_INFO: &str = "type.googleapis.com/google.rpc.ErrorInfo";
+const REQUEST_INFO: &str = "type.googleapis.com/google.rpc.RequestInfo";
+
+/// gRPC error detail attached to a failed operation
+///
+/// Known `@type`s are parsed into typed structs; anything else, or a known type with
+/// unexpected fields, is kept as `Other`
+#[derive(Clone, Debug, PartialEq)]
+pub enum ErrorDetail {
+    BadRequest(BadRequest),
+    QuotaFailure(QuotaFailure),
+    RetryInfo(RetryInfo),
+    ErrorInfo(ErrorInfo),
+    RequestInfo(RequestInfo),
+    Other(Value),
+}
+
+/// Request fields that failed validation
+#[derive(Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BadRequest {
+    #[serde(default)]
+    pub field_violations: Vec<FieldViolation>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldViolation {
+    pub field: String,
+    pub description: String,
+}
+
+/// Quotas that were exceeded
+#[derive(Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaFailure {
+    #[serde(default)]
+    pub violations: Vec<QuotaViolation>,
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaViolation {
+    pub subject: String,
+    pub description: String,
+}
+
+/// How long to wait before retrying
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryInfo {
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub retry_delay: Duration,
+}
+
+/// Machine-readable cause of the error
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorInfo {
+    pub reason: String,
+    #[serde(default)]
+    pub domain: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// Identifies the request for support
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestInfo {
+    pub request_id: String,
+    #[serde(default)]
+    pub serving_data: String,
+}
+
+impl<'de> Deserialize<'de> for ErrorDetail {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let typed = match value.get("@type").and_then(Value::as_str) {
+            Some(BAD_REQUEST) => parse(&value).map(ErrorDetail::BadRequest),
+            Some(QUOTA_FAILURE) => parse(&value).map(ErrorDetail::QuotaFailure),
+            Some(RETRY_INFO) => parse(&value).map(ErrorDetail::RetryInfo),
+            Some(ERROR_INFO) => parse(&value).map(ErrorDetail::ErrorInfo),
+            Some(REQUEST_INFO) => parse(&value).map(ErrorDetail::RequestInfo),
+            _ => None,
+        };
+        Ok(typed.unwrap_or(ErrorDetail::Other(value)))
+    }
+}
+
+fn parse<T: DeserializeOwned>(value: &Value) -> Option<T> {
+    serde_json::from_value(value.clone()).ok()
+}
+
+/// Parses a protobuf JSON duration, `"1.5s"` or `{"seconds": 1, "nanos": 500000000}`
+fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Wire {
+        Text(String),
+        Parts {
+            #[serde(default)]
+            seconds: Value,
+            #[serde(default)]
+            nanos: u32,
+        },
+    }
+
+    let invalid = |s: &str| serde::de::Error::custom(format!("invalid duration: {s}"));
+    match Wire::deserialize(deserializer)? {
+        Wire::Text(text) => text
+            .strip_suffix('s')
+            .and_then(|secs| secs.parse::<f64>().ok())
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+            .ok_or_else(|| invalid(&text)),
+        Wire::Parts { seconds, nanos } => {
+            // int64 fields are strings in protobuf JSON
+            let secs = match &seconds {
+                Value::Null => Some(0),
+                Value::Number(n) => n.as_u64(),
+                Value::String(s) => s.parse().ok(),
+                _ => None,
+            };
+            let secs = secs.ok_or_else(|| invalid(&seconds.to_string()))?;
+            Ok(Duration::new(secs, nanos))
+        }
+    }
+}
+
+/// Metadata of an operation
+///
+/// Image generation does not document its metadata fields, so they are kept as JSON
+/// alongside the protobuf `@type`. Metadata that is not a JSON object never fails parsing
+/// of the operation; it is kept in `unparsed`
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct OperationMetadata {
+    pub type_url: Option<String>,
+    pub fields: Map<String, Value>,
+    pub unparsed: Option<Value>,
+}
+
+impl<'de> Deserialize<'de> for OperationMetadata {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Object(mut fields) => {
+                let type_url = match fields.remove("@type") {
+                    Some(Value::String(type_url)) => Some(type_url),
+                    Some(other) => {
+                        fields.insert("@type".to_string(), other);
+                        None
+                    }
+                    None => None,
+                };
+                Ok(OperationMetadata { type_url, fields, unparsed: None })
+            }
+            other => Ok(OperationMetadata { unparsed: Some(other), ..Default::default() }),
+        }
+    }
+}
+
+impl OperationMetadata {
+    /// Returns a metadata field
+    pub fn get(&self, field: &str) -> Option<&Value> {
+        self.fields.get(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_details() {
+        let details: Vec<ErrorDetail> = serde_json::from_value(json!([
+            {"@type": BAD_REQUEST, "fieldViolations": [{"field": "messages[0].text", "description": "too long"}]},
+            {"@type": QUOTA_FAILURE, "violations": [{"subject": "folder b1g", "description": "images per hour"}]},
+            {"@type": RETRY_INFO, "retryDelay": "1.500s"},
+            {"@type": RETRY_INFO, "retryDelay": {"seconds": "2", "nanos": 0}},
+            {"@type": REQUEST_INFO, "requestId": "req-1"},
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            details[0],
+            ErrorDetail::BadRequest(BadRequest {
+                field_violations: vec![FieldViolation {
+                    field: "messages[0].text".to_string(),
+                    description: "too long".to_string()
+                }]
+            })
+        );
+        assert!(matches!(&details[1], ErrorDetail::QuotaFailure(q) if q.violations[0].subject == "folder b1g"));
+        assert_eq!(details[2], ErrorDetail::RetryInfo(RetryInfo { retry_delay: Duration::from_millis(1500) }));
+        assert_eq!(details[3], ErrorDetail::RetryInfo(RetryInfo { retry_delay: Duration::from_secs(2) }));
+        assert!(matches!(&details[4], ErrorDetail::RequestInfo(r) if r.request_id == "req-1"));
+    }
+
+    #[test]
+    fn test_untyped_fallback() {
+        let unknown = json!({"@type": "type.googleapis.com/google.rpc.Help", "links": []});
+        let malformed = json!({"@type": RETRY_INFO, "retryDelay": "soon"});
+        let details: Vec<ErrorDetail> = serde_json::from_value(json!([unknown, malformed, "text"])).unwrap();
+
+        assert_eq!(details, vec![ErrorDetail::Other(unknown), ErrorDetail::Other(malformed), ErrorDetail::Other(json!("text"))]);
+    }
+
+    #[test]
+    fn test_metadata() {
+        let metadata: OperationMetadata =
+            serde_json::from_value(json!({"@type": "type.googleapis.com/Meta", "progress": 50})).unwrap();
+        assert_eq!(metadata.type_url.as_deref(), Some("type.googleapis.com/Meta"));
+        assert_eq!(metadata.get("progress"), Some(&json!(50)));
+
+        let metadata: OperationMetadata = serde_json::from_value(json!("queued")).unwrap();
+        assert_eq!(metadata, OperationMetadata { unparsed: Some(json!("queued")), ..Default::default() });
+        let metadata: OperationMetadata = serde_json::from_value(json!({"@type": 1})).unwrap();
+        assert_eq!((metadata.type_url.as_deref(), metadata.get("@type")), (None, Some(&json!(1))));
+    }
+}
@@ -1,7 +1,12 @@
+use std::time::Duration;
+
 use serde::Deserialize;
-use serde_json::Value;
 use time::OffsetDateTime;
 
+pub mod error_details;
+
+use error_details::{ErrorDetail, FieldViolation, OperationMetadata, QuotaViolation};
+
 
 #[derive(Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -15,8 +20,39 @@ pub struct ResponseWrapper{
 pub struct ErrorWrapper{
     pub code: i64,
     pub message: String,
-    pub details: Option<Vec<Value>>,
-}   
+    pub details: Option<Vec<ErrorDetail>>,
+}
+
+impl ErrorWrapper {
+    /// Returns the error details, empty when there are none
+    pub fn details(&self) -> &[ErrorDetail] {
+        self.details.as_deref().unwrap_or_default()
+    }
+
+    /// Returns the request fields that failed validation
+    pub fn field_violations(&self) -> impl Iterator<Item = &FieldViolation> {
+        self.details().iter().flat_map(|detail| match detail {
+            ErrorDetail::BadRequest(bad_request) => bad_request.field_violations.as_slice(),
+            _ => &[],
+        })
+    }
+
+    /// Returns the exceeded quotas
+    pub fn quota_violations(&self) -> impl Iterator<Item = &QuotaViolation> {
+        self.details().iter().flat_map(|detail| match detail {
+            ErrorDetail::QuotaFailure(quota_failure) => quota_failure.violations.as_slice(),
+            _ => &[],
+        })
+    }
+
+    /// Returns the delay the server asks to wait before retrying
+    pub fn retry_delay(&self) -> Option<Duration> {
+        self.details().iter().find_map(|detail| match detail {
+            ErrorDetail::RetryInfo(retry_info) => Some(retry_info.retry_delay),
+            _ => None,
+        })
+    }
+}
 
 
 #[derive(Deserialize, Clone, Debug)]
@@ -34,7 +70,74 @@ pub struct Response{
     pub modified_at: Option<OffsetDateTime>,
 
     pub done: bool,
-    pub metadata: Option<Value>,
+    pub metadata: Option<OperationMetadata>,
     pub error: Option<ErrorWrapper>,
     pub response: Option<ResponseWrapper>,
-}
\ No newline at end of file
+}
+
+impl Response {
+    /// Returns the version of the model that generated the image of a finished operation
+    pub fn model_version(&self) -> Option<&str> {
+        self.response.as_ref()?.model_version.as_deref()
+    }
+
+    /// Returns the time between creation and the last modification of the operation
+    ///
+    /// # Returns
+    /// The generation time for a finished operation; `None` when either timestamp is
+    /// missing or `modified_at` is earlier than `created_at`
+    pub fn elapsed(&self) -> Option<Duration> {
+        let elapsed = self.modified_at? - self.created_at?;
+        Duration::try_from(elapsed).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_failed_operation() {
+        let response: Response = serde_json::from_value(json!({
+            "id": "op",
+            "createdAt": "2024-05-01T10:00:00Z",
+            "modifiedAt": "2024-05-01T10:00:12.5Z",
+            "done": true,
+            "metadata": null,
+            "error": {
+                "code": 8,
+                "message": "quota exceeded",
+                "details": [
+                    {"@type": "type.googleapis.com/google.rpc.QuotaFailure", "violations": [{"subject": "folder", "description": "images per hour"}]},
+                    {"@type": "type.googleapis.com/google.rpc.RetryInfo", "retryDelay": "30s"},
+                    {"@type": "type.googleapis.com/google.rpc.Help"}
+                ]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(response.elapsed(), Some(Duration::from_millis(12_500)));
+        let error = response.error.unwrap();
+        assert_eq!(error.details().len(), 3);
+        assert_eq!(error.quota_violations().map(|v| v.description.as_str()).collect::<Vec<_>>(), ["images per hour"]);
+        assert_eq!(error.field_violations().count(), 0);
+        assert_eq!(error.retry_delay(), Some(Duration::from_secs(30)));
+        assert!(matches!(error.details()[2], ErrorDetail::Other(_)));
+    }
+
+    #[test]
+    fn test_non_object_metadata_is_kept() {
+        let response: Response =
+            serde_json::from_value(json!({"id": "op", "done": false, "metadata": [1, 2]})).unwrap();
+        assert_eq!(response.metadata.unwrap().unparsed, Some(json!([1, 2])));
+    }
+
+    #[test]
+    fn test_elapsed_requires_both_timestamps() {
+        let response: Response =
+            serde_json::from_value(json!({"id": "op", "createdAt": "2024-05-01T10:00:00Z", "done": false})).unwrap();
+        assert_eq!(response.elapsed(), None);
+        assert_eq!(response.error.map(|e| e.details().len()), None);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::error::{ArtError, BuildError};
+use crate::models::request::Request;
+
+/// Maximum number of seeds of one sweep
+pub const MAX_SEEDS: usize = 1000;
+
+/// Seeds to generate variations of a request with
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SeedStrategy {
+    /// The given seeds in order; repeated seeds are generated once
+    List(Vec<i64>),
+    /// The given number of distinct random non-negative seeds
+    Random(usize),
+    /// Every seed of the range, e.g. `0..8`
+    Range(Range<i64>),
+}
+
+impl SeedStrategy {
+    /// Returns the seeds to generate, without repeats
+    ///
+    /// Fails with `BuildError::TooManySeeds` for more than `MAX_SEEDS` seeds
+    pub fn seeds(&self) -> Result<Vec<i64>, BuildError> {
+        let too_many = |count| BuildError::TooManySeeds { count, max: MAX_SEEDS };
+        match self {
+            SeedStrategy::List(seeds) => {
+                let mut seen = HashSet::new();
+                let seeds: Vec<i64> = seeds.iter().copied().filter(|seed| seen.insert(*seed)).collect();
+                match seeds.len() {
+                    count if count > MAX_SEEDS => Err(too_many(count)),
+                    _ => Ok(seeds),
+                }
+            }
+            SeedStrategy::Random(count) if *count > MAX_SEEDS => Err(too_many(*count)),
+            SeedStrategy::Random(count) => {
+                let mut seen = HashSet::with_capacity(*count);
+                let mut seeds = Vec::with_capacity(*count);
+                while seeds.len() < *count {
+                    let seed = rand::random_range(0..=i64::MAX);
+                    if seen.insert(seed) {
+                        seeds.push(seed);
+                    }
+                }
+                Ok(seeds)
+            }
+            SeedStrategy::Range(range) => {
+                let count = (range.end as i128 - range.start as i128).max(0);
+                match usize::try_from(count) {
+                    Ok(count) if count <= MAX_SEEDS => Ok(range.clone().collect()),
+                    Ok(count) => Err(too_many(count)),
+                    Err(_) => Err(too_many(usize::MAX)),
+                }
+            }
+        }
+    }
+}
+
+impl From<Vec<i64>> for SeedStrategy {
+    fn from(seeds: Vec<i64>) -> Self {
+        SeedStrategy::List(seeds)
+    }
+}
+
+impl From<Range<i64>> for SeedStrategy {
+    fn from(range: Range<i64>) -> Self {
+        SeedStrategy::Range(range)
+    }
+}
+
+/// Settings of a seed sweep
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VariationOptions {
+    concurrency: usize,
+    poll_interval: Duration,
+    timeout: Duration,
+}
+
+impl VariationOptions {
+    /// 4 operations at once, polled every 2 seconds for up to 5 minutes each
+    pub fn new() -> Self {
+        VariationOptions { concurrency: 4, poll_interval: Duration::from_secs(2), timeout: Duration::from_secs(300) }
+    }
+
+    /// Maximum number of operations started and awaited at once (at least 1, default 4)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Delay between status checks of each operation (default 2 seconds)
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// How long to wait for each operation (default 5 minutes)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl Default for VariationOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Image generated for one seed of a variation run
+///
+/// `request` is the request that was sent, with `seed` set; generating it again with the
+/// same `model_version` reproduces the image
+#[derive(Clone, Debug)]
+pub struct GeneratedImage {
+    pub seed: i64,
+    pub request: Request,
+    pub operation_id: String,
+    /// Base64-encoded image
+    pub image: String,
+    pub model_version: Option<String>,
+}
+
+/// Failed generation of one seed of a variation run
+#[derive(Debug)]
+pub struct VariationError {
+    pub seed: i64,
+    pub error: ArtError,
+}
+
+impl std::fmt::Display for VariationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Seed {}: {}", self.seed, self.error)
+    }
+}
+
+impl std::error::Error for VariationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeds() {
+        assert_eq!(SeedStrategy::from(vec![3, 1, 3, 2]).seeds(), Ok(vec![3, 1, 2]));
+        assert_eq!(SeedStrategy::from(5..8).seeds(), Ok(vec![5, 6, 7]));
+
+        let seeds = SeedStrategy::Random(16).seeds().unwrap();
+        assert_eq!(seeds.len(), 16);
+        assert_eq!(seeds.iter().collect::<HashSet<_>>().len(), 16);
+        assert!(seeds.iter().all(|seed| *seed >= 0));
+    }
+
+    #[test]
+    fn test_seed_count_is_limited() {
+        let too_many = |count| Err(BuildError::TooManySeeds { count, max: MAX_SEEDS });
+
+        assert_eq!(SeedStrategy::Random(MAX_SEEDS).seeds().map(|s| s.len()), Ok(MAX_SEEDS));
+        assert_eq!(SeedStrategy::Random(usize::MAX).seeds(), too_many(usize::MAX));
+        assert_eq!(SeedStrategy::from(0..MAX_SEEDS as i64 + 1).seeds(), too_many(MAX_SEEDS + 1));
+        assert_eq!(SeedStrategy::from(0..i64::MAX).seeds(), too_many(i64::MAX as usize));
+        assert_eq!(SeedStrategy::from(i64::MIN..i64::MAX).seeds(), too_many(usize::MAX));
+        assert_eq!(SeedStrategy::from((0..=MAX_SEEDS as i64).collect::<Vec<_>>()).seeds(), too_many(MAX_SEEDS + 1));
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::{BufRead, Write, stdin, stdout};
+use std::path::PathBuf;
+
+use anyhow::{Context, anyhow};
+use clap::Args;
+use nn_yandex_core::config::Config;
+use nn_yandex_gpt::TextGenerator;
+use nn_yandex_gpt::models::message::{Message, MessageBuilder, Role};
+use nn_yandex_gpt::models::request::RequestBuilder;
+
+use crate::ModelArgs;
+use crate::complete::warn_if_incomplete;
+
+#[derive(Args)]
+pub struct ChatArgs {
+    #[command(flatten)]
+    model: ModelArgs,
+
+    /// System prompt for a new conversation
+    #[arg(long)]
+    system: Option<String>,
+
+    /// JSON file with the conversation; loaded on start and rewritten after every turn
+    #[arg(long)]
+    history: Option<PathBuf>,
+}
+
+pub async fn run(config: &Config, args: ChatArgs) -> anyhow::Result<()> {
+    let generator = TextGenerator::from_config(config)?;
+    let model = args.model.select(&generator);
+    let mut messages = match &args.history {
+        Some(path) if path.exists() => load_history(path)?,
+        _ => Vec::new(),
+    };
+    if let (true, Some(system)) = (messages.is_empty(), &args.system) {
+        messages.push(MessageBuilder::new().with_role(Role::System).with_text(system).build());
+    }
+
+    println!("Commands: /reset clears the conversation, /exit quits");
+    let mut lines = stdin().lock().lines();
+    loop {
+        print!("> ");
+        stdout().flush()?;
+
+        let Some(line) = lines.next() else { break };
+        let line = line?;
+        match line.trim() {
+            "" => continue,
+            "/exit" | "/quit" => break,
+            "/reset" => {
+                messages.retain(|m| matches!(m.role, Role::System));
+                save_history(args.history.as_ref(), &messages)?;
+                continue;
+            }
+            _ => {}
+        }
+
+        messages.push(MessageBuilder::new().with_role(Role::User).with_text(&line).build());
+
+        let mut builder = RequestBuilder::new().with_completion_options(args.model.completion_options());
+        for message in &messages {
+            builder = builder.message(message.clone());
+        }
+
+        match generator.complete(model.clone(), builder.build()).await {
+            Ok(result) => {
+                let alternative = result
+                    .alternative()
+                    .ok_or_else(|| anyhow!("Response contains no alternatives"))?;
+                println!("{}", alternative.message.text);
+                warn_if_incomplete(&result);
+                messages.push(alternative.message.clone());
+                save_history(args.history.as_ref(), &messages)?;
+            }
+            Err(err) => {
+                messages.pop();
+                eprintln!("Error: {err}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn load_history(path: &PathBuf) -> anyhow::Result<Vec<Message>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read history {}", path.display()))?;
+    serde_json::from_str(&text).with_context(|| format!("Invalid history file {}", path.display()))
+}
+
+fn save_history(path: Option<&PathBuf>, messages: &[Message]) -> anyhow::Result<()> {
+    let Some(path) = path else { return Ok(()) };
+    let text = serde_json::to_string_pretty(messages)?;
+    std::fs::write(path, text).with_context(|| format!("Failed to write history {}", path.display()))
+}
@@ -0,0 +1,82 @@
+use std::io::Read;
+use std::path::PathBuf;
+
+use anyhow::{Context, anyhow};
+use clap::Args;
+use nn_yandex_core::config::Config;
+use nn_yandex_gpt::TextGenerator;
+use nn_yandex_gpt::models::response::Result as YandexResult;
+use nn_yandex_gpt::models::message::{MessageBuilder, Role};
+use nn_yandex_gpt::models::request::{JsonSchema, RequestBuilder};
+
+use crate::ModelArgs;
+
+#[derive(Args)]
+pub struct CompleteArgs {
+    #[command(flatten)]
+    model: ModelArgs,
+
+    /// System prompt
+    #[arg(long)]
+    system: Option<String>,
+
+    /// JSON schema file the answer must conform to
+    #[arg(long)]
+    schema: Option<PathBuf>,
+
+    /// Print the full API response as JSON instead of the answer text
+    #[arg(long)]
+    raw: bool,
+
+    /// Prompt text; read from stdin when omitted
+    prompt: Vec<String>,
+}
+
+pub async fn run(config: &Config, args: CompleteArgs) -> anyhow::Result<()> {
+    let prompt = if args.prompt.is_empty() {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        text
+    } else {
+        args.prompt.join(" ")
+    };
+    if prompt.trim().is_empty() {
+        return Err(anyhow!("Prompt is empty"));
+    }
+
+    let mut builder = RequestBuilder::new().with_completion_options(args.model.completion_options());
+    if let Some(system) = &args.system {
+        builder = builder.message(MessageBuilder::new().with_role(Role::System).with_text(system).build());
+    }
+    builder = builder.message(MessageBuilder::new().with_role(Role::User).with_text(&prompt).build());
+    if let Some(path) = &args.schema {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read schema {}", path.display()))?;
+        let schema = serde_json::from_str(&text)
+            .with_context(|| format!("Invalid JSON in schema {}", path.display()))?;
+        builder = builder.with_json_schema(JsonSchema { schema });
+    }
+
+    let generator = TextGenerator::from_config(config)?;
+    let model = args.model.select(&generator);
+    let result = generator.complete(model, builder.build()).await?;
+
+    if args.raw {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        let text = result.text().ok_or_else(|| anyhow!("Response contains no alternatives"))?;
+        println!("{text}");
+        warn_if_incomplete(&result);
+    }
+
+    Ok(())
+}
+
+/// Prints a warning to stderr when the answer was cut off or filtered
+pub fn warn_if_incomplete(result: &YandexResult) {
+    if result.is_truncated() {
+        eprintln!("warning: the answer was truncated; increase --max-tokens");
+    } else if result.is_content_filtered() {
+        eprintln!("warning: the answer was blocked by the content filter");
+    }
+}
@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use clap::{Args, ValueEnum};
+use nn_yandex_art::Art;
+use nn_yandex_art::error::ArtError;
+use nn_yandex_art::models::model_type::ArtModel;
+use nn_yandex_art::models::request::RequestBuilder;
+use nn_yandex_art::models::request::aspect_ratio::{AspectRatio, AspectRatioBuilder, AspectRatioPreset};
+use nn_yandex_art::models::request::generation_options::GenerationOptionsBuilder;
+use nn_yandex_art::models::request::message::MessageBuilder;
+use nn_yandex_art::models::request::types::ImageType;
+use nn_yandex_art::models::response::Response;
+use nn_yandex_core::config::Config;
+
+#[derive(Args)]
+pub struct ImageArgs {
+    /// Image description
+    prompt: String,
+
+    /// Aspect ratio as WIDTH:HEIGHT or a preset (square, widescreen, vertical, standard,
+    /// photo, banner, story)
+    #[arg(long, default_value = "1:1", value_parser = parse_aspect_ratio)]
+    aspect_ratio: AspectRatio,
+
+    /// Model as `name` or `name/version`, e.g. `yandex-art/rc`; defaults to `yandex-art/latest`
+    #[arg(long)]
+    model: Option<ArtModel>,
+
+    /// Seed for reproducible results
+    #[arg(long)]
+    seed: Option<i64>,
+
+    /// Image format
+    #[arg(long, value_enum, default_value_t = MimeArg::Png)]
+    mime: MimeArg,
+
+    /// Where to save the image (requires --wait); defaults to image.<format>
+    #[arg(long, short, requires = "wait")]
+    output: Option<PathBuf>,
+
+    /// Wait for the operation to finish and save the image instead of printing its ID
+    #[arg(long)]
+    wait: bool,
+
+    /// Seconds between status checks while waiting
+    #[arg(long, default_value_t = 2)]
+    poll_interval: u64,
+
+    /// Seconds to wait for the operation before giving up
+    #[arg(long, default_value_t = 300)]
+    timeout: u64,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MimeArg {
+    Png,
+    Jpeg,
+}
+
+impl MimeArg {
+    fn image_type(self) -> ImageType {
+        match self {
+            MimeArg::Png => ImageType::Png,
+            MimeArg::Jpeg => ImageType::Jpeg,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            MimeArg::Png => "png",
+            MimeArg::Jpeg => "jpeg",
+        }
+    }
+}
+
+pub async fn run(config: &Config, args: ImageArgs) -> anyhow::Result<()> {
+    let message = MessageBuilder::new().text(&args.prompt).build()?;
+    let mut options = GenerationOptionsBuilder::new()
+        .mime_type(args.mime.image_type())
+        .aspect_ratio(args.aspect_ratio);
+    if let Some(seed) = args.seed {
+        options = options.seed(seed);
+    }
+
+    let mut request = RequestBuilder::new()
+        .message(message)
+        .generation_options(options.build()?);
+    if let Some(model) = args.model {
+        request = request.model(model);
+    }
+    let request = request.build()?;
+
+    let art = Art::from_config(config)?;
+    let operation = match art.generate_image(request).await {
+        Err(ArtError::Rejected { status, error }) => {
+            for violation in error.field_violations() {
+                eprintln!("{}: {}", violation.field, violation.description);
+            }
+            anyhow::bail!("request rejected ({status}): {}", error.message);
+        }
+        result => result?,
+    };
+
+    if !args.wait {
+        println!("{}", operation.id);
+        return Ok(());
+    }
+
+    let result = art
+        .wait_for_operation(
+            &operation.id,
+            Duration::from_secs(args.poll_interval),
+            Duration::from_secs(args.timeout),
+        )
+        .await?;
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("image.{}", args.mime.extension())));
+    save_image(&result, &output)?;
+    println!("{}", output.display());
+    if let Some(version) = result.model_version() {
+        eprintln!("model version: {version}");
+    }
+
+    Ok(())
+}
+
+/// Decodes the image of a finished operation and writes it to `path`
+pub fn save_image(response: &Response, path: &Path) -> anyhow::Result<()> {
+    let image = response
+        .response
+        .as_ref()
+        .ok_or_else(|| anyhow!("Response is missing image data"))?;
+    let bytes = STANDARD.decode(&image.image).context("Image is not valid base64")?;
+    std::fs::write(path, bytes).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn parse_aspect_ratio(value: &str) -> Result<AspectRatio, String> {
+    if let Ok(preset) = value.parse::<AspectRatioPreset>() {
+        return Ok(preset.into());
+    }
+    let (width, height) = value
+        .split_once(':')
+        .ok_or_else(|| "expected WIDTH:HEIGHT".to_string())?;
+    let width = width.trim().parse().map_err(|e| format!("invalid width: {e}"))?;
+    let height = height.trim().parse().map_err(|e| format!("invalid height: {e}"))?;
+    AspectRatioBuilder::new()
+        .width_ratio(width)
+        .height_ratio(height)
+        .build()
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,99 @@
+mod chat;
+mod complete;
+mod image;
+mod operation;
+
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use nn_yandex_core::config::Config;
+use nn_yandex_gpt::TextGenerator;
+use nn_yandex_gpt::models::model_uri::ModelUri;
+use nn_yandex_gpt::models::request::CompletionOptions;
+
+/// Command-line client for Yandex Foundation Models
+///
+/// Settings are read from the config file (default ~/.config/nn-yandex/config.toml)
+/// and YANDEX_* environment variables, which take precedence
+#[derive(Parser)]
+#[command(name = "nn-yandex", version, about)]
+struct Cli {
+    /// Path to a TOML or YAML config file
+    #[arg(long, global = true, env = "NN_YANDEX_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Config file profile [env: YANDEX_PROFILE]
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Interactive chat with conversation history
+    Chat(chat::ChatArgs),
+    /// One-shot text completion from arguments or stdin
+    Complete(complete::CompleteArgs),
+    /// Generate an image
+    Image(image::ImageArgs),
+    /// Inspect or cancel an image generation operation
+    #[command(subcommand)]
+    Operation(operation::OperationCommand),
+}
+
+/// Model selection shared by text commands
+#[derive(Args)]
+struct ModelArgs {
+    /// Model as `name`, `name/version` or a full URI (`gpt://...`, `ds://...`);
+    /// defaults to the configured model
+    #[arg(long)]
+    model: Option<ModelUri>,
+
+    /// Sampling temperature
+    #[arg(long)]
+    temperature: Option<f64>,
+
+    /// Maximum number of tokens to generate
+    #[arg(long)]
+    max_tokens: Option<i64>,
+}
+
+impl ModelArgs {
+    fn select(&self, generator: &TextGenerator) -> ModelUri {
+        self.model.clone().unwrap_or_else(|| generator.default_model().clone())
+    }
+
+    fn completion_options(&self) -> CompletionOptions {
+        let mut options = CompletionOptions::new();
+        if let Some(temperature) = self.temperature {
+            options = options.with_temperature(temperature);
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            options = options.with_max_tokens(max_tokens);
+        }
+        options
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let path = cli.config.or_else(|| default_config_path().filter(|path| path.exists()));
+    let config = Config::load(path.as_deref(), cli.profile.as_deref())?;
+
+    match cli.command {
+        Command::Chat(args) => chat::run(&config, args).await,
+        Command::Complete(args) => complete::run(&config, args).await,
+        Command::Image(args) => image::run(&config, args).await,
+        Command::Operation(command) => operation::run(&config, command).await,
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("nn-yandex").join("config.toml"))
+}
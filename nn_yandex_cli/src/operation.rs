@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use nn_yandex_art::Art;
+use nn_yandex_art::error::ArtError;
+use nn_yandex_art::models::response::{ErrorWrapper, Response};
+use nn_yandex_core::config::Config;
+
+use crate::image::save_image;
+
+#[derive(Subcommand)]
+pub enum OperationCommand {
+    /// Print the status of an operation, saving the image when it is done
+    Get {
+        /// Operation ID
+        id: String,
+
+        /// Where to save the image if the operation is done
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Cancel an operation
+    Cancel {
+        /// Operation ID
+        id: String,
+    },
+}
+
+pub async fn run(config: &Config, command: OperationCommand) -> anyhow::Result<()> {
+    let art = Art::from_config(config)?;
+
+    match command {
+        OperationCommand::Get { id, output } => {
+            let response = match art.check_operation(&id).await {
+                Err(ArtError::Operation(error)) => {
+                    println!("id: {id}");
+                    println!("done: true");
+                    print_error(&error);
+                    anyhow::bail!("operation {id} failed");
+                }
+                result => result?,
+            };
+            print_status(&response);
+            if let (true, Some(path)) = (response.done, output) {
+                save_image(&response, &path)?;
+                println!("saved: {}", path.display());
+            }
+        }
+        OperationCommand::Cancel { id } => {
+            let response = art.cancel_operation(&id).await?;
+            print_status(&response);
+        }
+    }
+
+    Ok(())
+}
+
+fn print_status(response: &Response) {
+    println!("id: {}", response.id);
+    println!("done: {}", response.done);
+    if let Some(created_at) = response.created_at {
+        println!("created at: {created_at}");
+    }
+    if let Some(modified_at) = response.modified_at {
+        println!("modified at: {modified_at}");
+    }
+    if let Some(error) = &response.error {
+        print_error(error);
+    }
+}
+
+fn print_error(error: &ErrorWrapper) {
+    println!("error: {} (code {})", error.message, error.code);
+    for violation in error.field_violations() {
+        println!("  {}: {}", violation.field, violation.description);
+    }
+    for violation in error.quota_violations() {
+        println!("  {}: {}", violation.subject, violation.description);
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::secret::Secret;
+
+/// Authentication method for Yandex Cloud API
+///
+/// Credentials are kept in `Secret`, so `Debug` output never contains them
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Auth {
+    /// Service account API key, sent as `Api-Key <key>`
+    ApiKey(Secret),
+    /// IAM token, sent as `Bearer <token>`
+    IamToken(Secret),
+}
+
+impl Auth {
+    /// Authenticates with a service account API key
+    pub fn api_key(key: impl Into<Secret>) -> Self {
+        Auth::ApiKey(key.into())
+    }
+
+    /// Authenticates with an IAM token
+    pub fn iam_token(token: impl Into<Secret>) -> Self {
+        Auth::IamToken(token.into())
+    }
+
+    /// Returns the value of the `Authorization` header
+    ///
+    /// Surrounding whitespace of the credential, such as the trailing newline of a key read
+    /// from a file, is dropped
+    pub fn header_value(&self) -> Secret {
+        match self {
+            Auth::ApiKey(key) => Secret::new(format!("Api-Key {}", key.expose().trim())),
+            Auth::IamToken(token) => Secret::new(format!("Bearer {}", token.expose().trim())),
+        }
+    }
+}
+
+/// Credentials that can be replaced at runtime without locking
+///
+/// Clones share the same cell, so a handle obtained from a client (or passed to several
+/// clients) rotates credentials for all of them. Every request attempt reads the current
+/// value; requests already in flight keep the credentials they were sent with.
+#[derive(Clone, Debug)]
+pub struct CredentialCell(Arc<ArcSwap<Auth>>);
+
+impl CredentialCell {
+    pub fn new(auth: Auth) -> Self {
+        CredentialCell(Arc::new(ArcSwap::from_pointee(auth)))
+    }
+
+    /// Returns the current credentials
+    pub fn load(&self) -> Arc<Auth> {
+        self.0.load_full()
+    }
+
+    /// Replaces the credentials for all clients sharing this cell
+    pub fn rotate(&self, auth: Auth) {
+        self.0.store(Arc::new(auth));
+    }
+}
+
+impl From<Auth> for CredentialCell {
+    fn from(auth: Auth) -> Self {
+        CredentialCell::new(auth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_value() {
+        assert_eq!(Auth::api_key("key").header_value().expose(), "Api-Key key");
+        assert_eq!(Auth::iam_token("token").header_value().expose(), "Bearer token");
+        assert_eq!(Auth::api_key(" key\n").header_value().expose(), "Api-Key key");
+    }
+
+    #[test]
+    fn test_debug_is_redacted() {
+        assert_eq!(format!("{:?}", Auth::api_key("key")), "ApiKey(Secret([REDACTED]))");
+    }
+
+    #[test]
+    fn test_rotation_is_shared_between_clones() {
+        let cell = CredentialCell::new(Auth::api_key("old"));
+        let handle = cell.clone();
+        let before = cell.load();
+
+        handle.rotate(Auth::iam_token("new"));
+
+        assert_eq!(*cell.load(), Auth::iam_token("new"));
+        assert_eq!(*before, Auth::api_key("old"));
+    }
+}
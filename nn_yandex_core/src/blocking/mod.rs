@@ -0,0 +1,62 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use tokio::runtime::{Builder, Runtime};
+
+/// Single-threaded runtime that drives the async clients for their blocking wrappers
+///
+/// Clones share the runtime. `block_on` may be called from several threads at once,
+/// but not from inside another async runtime.
+#[derive(Clone, Debug)]
+pub struct BlockingRuntime(Arc<Runtime>);
+
+impl BlockingRuntime {
+    /// Creates the runtime
+    ///
+    /// # Panics
+    ///
+    /// Panics if the runtime cannot be created (the OS refuses to create its I/O driver)
+    pub fn new() -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the runtime for blocking clients");
+        BlockingRuntime(Arc::new(runtime))
+    }
+
+    /// Runs `future` to completion on the current thread
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.0.block_on(future)
+    }
+}
+
+impl Default for BlockingRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_block_on_from_several_threads() {
+        let runtime = BlockingRuntime::new();
+        let handles: Vec<_> = (0..4u64)
+            .map(|i| {
+                let runtime = runtime.clone();
+                std::thread::spawn(move || {
+                    runtime.block_on(async move {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        i
+                    })
+                })
+            })
+            .collect();
+
+        let results: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(results, [0, 1, 2, 3]);
+    }
+}
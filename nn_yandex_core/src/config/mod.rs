@@ -0,0 +1,452 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::auth::Auth;
+use crate::error::ConfigError;
+use crate::retry::RetryPolicy;
+use crate::secret::Secret;
+
+pub const FOLDER_ID_ENV: &str = "YANDEX_FOLDER_ID";
+pub const API_KEY_ENV: &str = "YANDEX_API_KEY";
+pub const IAM_TOKEN_ENV: &str = "YANDEX_IAM_TOKEN";
+pub const PROFILE_ENV: &str = "YANDEX_PROFILE";
+pub const MODEL_ENV: &str = "YANDEX_MODEL";
+pub const MODEL_VERSION_ENV: &str = "YANDEX_MODEL_VERSION";
+pub const TIMEOUT_ENV: &str = "YANDEX_TIMEOUT_SECS";
+pub const CONNECT_TIMEOUT_ENV: &str = "YANDEX_CONNECT_TIMEOUT_SECS";
+
+/// Endpoint overrides; `None` means the client's built-in URL
+#[derive(Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Endpoints {
+    /// Text completion endpoint
+    pub completion: Option<String>,
+    /// Asynchronous image generation endpoint
+    pub image_generation: Option<String>,
+    /// Operations endpoint used to poll and cancel image generations
+    pub operations: Option<String>,
+}
+
+/// Settings shared by TextGenerator and Art
+///
+/// Can be built in code or loaded from a TOML/YAML file and environment variables.
+/// Top-level keys of the file form the default profile; tables under `[profiles.<name>]`
+/// override it:
+///
+/// ```toml
+/// folder_id = "b1g..."
+/// api_key = "AQVN..."
+/// model = "yandexgpt-lite"
+/// version = "latest"
+/// timeout_secs = 60
+///
+/// [retry]
+/// max_retries = 3
+/// initial_backoff_ms = 500
+///
+/// [profiles.prod]
+/// folder_id = "b1g..."
+/// iam_token = "t1..."
+/// model = "yandexgpt"
+/// ```
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub folder_id: String,
+    pub auth: Auth,
+    pub endpoints: Endpoints,
+    /// Default text model (URI segment, e.g. `yandexgpt-lite`)
+    pub model: Option<String>,
+    /// Default text model version (e.g. `latest`, `rc`)
+    pub version: Option<String>,
+    /// Total timeout of a single HTTP request
+    pub timeout: Option<Duration>,
+    /// Timeout for establishing a connection
+    pub connect_timeout: Option<Duration>,
+    pub retry_policy: RetryPolicy,
+}
+
+impl Config {
+    /// Creates a config with built-in endpoints, no timeouts and the default retry policy
+    pub fn new(folder_id: impl Into<String>, auth: Auth) -> Self {
+        Config {
+            folder_id: folder_id.into(),
+            auth,
+            endpoints: Endpoints::default(),
+            model: None,
+            version: None,
+            timeout: None,
+            connect_timeout: None,
+            retry_policy: RetryPolicy::new(),
+        }
+    }
+
+    pub fn with_endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    pub fn with_model(mut self, model: &str, version: &str) -> Self {
+        self.model = Some(model.to_string());
+        self.version = Some(version.to_string());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Reads the config from environment variables only
+    pub fn from_env() -> Result<Self, ConfigError> {
+        env_layer(|name| std::env::var(name).ok())?.resolve()
+    }
+
+    /// Reads the config from a file only
+    ///
+    /// `.yaml`/`.yml` files are parsed as YAML, everything else as TOML
+    pub fn from_file(path: impl AsRef<Path>, profile: Option<&str>) -> Result<Self, ConfigError> {
+        read_file(path.as_ref())?.profile(profile)?.resolve()
+    }
+
+    /// Reads the config from an optional file and lets environment variables override it
+    ///
+    /// The profile defaults to `YANDEX_PROFILE` when not given explicitly
+    pub fn load(path: Option<&Path>, profile: Option<&str>) -> Result<Self, ConfigError> {
+        let env_profile = std::env::var(PROFILE_ENV).ok();
+        let profile = profile.or(env_profile.as_deref());
+        let file = match path {
+            Some(path) => read_file(path)?.profile(profile)?,
+            None => Layer::default(),
+        };
+        file.merge(env_layer(|name| std::env::var(name).ok())?).resolve()
+    }
+
+    /// Builds an HTTP client honoring the configured timeouts
+    pub fn http_client(&self) -> Result<reqwest::Client, ConfigError> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        builder
+            .build()
+            .map_err(|e| ConfigError::Invalid { field: "http_client", message: e.to_string() })
+    }
+}
+
+/// One source of settings (a file profile or the environment); later layers win
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct Layer {
+    folder_id: Option<String>,
+    api_key: Option<Secret>,
+    iam_token: Option<Secret>,
+    model: Option<String>,
+    version: Option<String>,
+    timeout_secs: Option<u64>,
+    connect_timeout_secs: Option<u64>,
+    #[serde(default)]
+    endpoints: Endpoints,
+    #[serde(default)]
+    retry: RetryLayer,
+    /// Named profiles; only allowed at the top level of a file
+    #[serde(default)]
+    profiles: HashMap<String, Layer>,
+}
+
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+struct RetryLayer {
+    max_retries: Option<u32>,
+    initial_backoff_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+}
+
+/// Parsed config file: the top-level settings and the profiles that override them
+#[derive(Debug)]
+struct ConfigFile {
+    base: Layer,
+    profiles: HashMap<String, Layer>,
+}
+
+impl ConfigFile {
+    fn profile(mut self, name: Option<&str>) -> Result<Layer, ConfigError> {
+        match name {
+            None => Ok(self.base),
+            Some(name) => {
+                let profile = self
+                    .profiles
+                    .remove(name)
+                    .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))?;
+                Ok(self.base.merge(profile))
+            }
+        }
+    }
+}
+
+impl Layer {
+    fn merge(self, over: Layer) -> Layer {
+        // Credentials are replaced as a unit so an API key from one layer is never
+        // combined with an IAM token from another
+        let (api_key, iam_token) = if over.api_key.is_some() || over.iam_token.is_some() {
+            (over.api_key, over.iam_token)
+        } else {
+            (self.api_key, self.iam_token)
+        };
+
+        Layer {
+            folder_id: over.folder_id.or(self.folder_id),
+            api_key,
+            iam_token,
+            model: over.model.or(self.model),
+            version: over.version.or(self.version),
+            timeout_secs: over.timeout_secs.or(self.timeout_secs),
+            connect_timeout_secs: over.connect_timeout_secs.or(self.connect_timeout_secs),
+            endpoints: Endpoints {
+                completion: over.endpoints.completion.or(self.endpoints.completion),
+                image_generation: over.endpoints.image_generation.or(self.endpoints.image_generation),
+                operations: over.endpoints.operations.or(self.endpoints.operations),
+            },
+            retry: RetryLayer {
+                max_retries: over.retry.max_retries.or(self.retry.max_retries),
+                initial_backoff_ms: over.retry.initial_backoff_ms.or(self.retry.initial_backoff_ms),
+                max_backoff_ms: over.retry.max_backoff_ms.or(self.retry.max_backoff_ms),
+            },
+            profiles: HashMap::new(),
+        }
+    }
+
+    fn resolve(self) -> Result<Config, ConfigError> {
+        let folder_id = self.folder_id.ok_or(ConfigError::Missing("folder_id"))?;
+        let auth = match (self.api_key, self.iam_token) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::Invalid {
+                    field: "auth",
+                    message: "both api_key and iam_token are set".to_string(),
+                });
+            }
+            (Some(key), None) => Auth::ApiKey(key),
+            (None, Some(token)) => Auth::IamToken(token),
+            (None, None) => return Err(ConfigError::Missing("api_key or iam_token")),
+        };
+
+        let mut retry_policy = RetryPolicy::new();
+        if let Some(max_retries) = self.retry.max_retries {
+            retry_policy = retry_policy.with_max_retries(max_retries);
+        }
+        if let Some(ms) = self.retry.initial_backoff_ms {
+            retry_policy = retry_policy.with_initial_backoff(Duration::from_millis(ms));
+        }
+        if let Some(ms) = self.retry.max_backoff_ms {
+            retry_policy = retry_policy.with_max_backoff(Duration::from_millis(ms));
+        }
+
+        Ok(Config {
+            folder_id,
+            auth,
+            endpoints: self.endpoints,
+            model: self.model,
+            version: self.version,
+            timeout: self.timeout_secs.map(Duration::from_secs),
+            connect_timeout: self.connect_timeout_secs.map(Duration::from_secs),
+            retry_policy,
+        })
+    }
+}
+
+fn read_file(path: &Path) -> Result<ConfigFile, ConfigError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|source| ConfigError::Io { path: path.to_path_buf(), source })?;
+    parse_file(path, &text)
+}
+
+fn parse_file(path: &Path, text: &str) -> Result<ConfigFile, ConfigError> {
+    let parse_error = |message: String| ConfigError::Parse { path: PathBuf::from(path), message };
+    // The top level is parsed as a Layer rather than a flattened one, which would make
+    // serde ignore unknown fields
+    let mut base: Layer = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yaml" | "yml") => serde_yaml::from_str(text).map_err(|e| parse_error(e.to_string()))?,
+        _ => toml::from_str(text).map_err(|e| parse_error(e.to_string()))?,
+    };
+    let profiles = std::mem::take(&mut base.profiles);
+    if let Some(name) = profiles.iter().find_map(|(name, profile)| (!profile.profiles.is_empty()).then_some(name)) {
+        return Err(parse_error(format!("profile {name} defines nested profiles")));
+    }
+    Ok(ConfigFile { base, profiles })
+}
+
+fn env_layer(lookup: impl Fn(&str) -> Option<String>) -> Result<Layer, ConfigError> {
+    let seconds = |name: &'static str| match lookup(name) {
+        Some(value) => value.parse().map(Some).map_err(|_| ConfigError::Invalid {
+            field: name,
+            message: format!("expected a number of seconds, got {value:?}"),
+        }),
+        None => Ok(None),
+    };
+    let timeout_secs = seconds(TIMEOUT_ENV)?;
+    let connect_timeout_secs = seconds(CONNECT_TIMEOUT_ENV)?;
+
+    Ok(Layer {
+        folder_id: lookup(FOLDER_ID_ENV),
+        api_key: lookup(API_KEY_ENV).map(Secret::from),
+        iam_token: lookup(IAM_TOKEN_ENV).map(Secret::from),
+        model: lookup(MODEL_ENV),
+        version: lookup(MODEL_VERSION_ENV),
+        timeout_secs,
+        connect_timeout_secs,
+        ..Layer::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+folder_id = "base-folder"
+api_key = "base-key"
+model = "yandexgpt-lite"
+timeout_secs = 30
+
+[retry]
+max_retries = 5
+
+[endpoints]
+completion = "http://localhost/completion"
+
+[profiles.prod]
+folder_id = "prod-folder"
+iam_token = "prod-token"
+model = "yandexgpt"
+version = "rc"
+"#;
+
+    fn toml_file() -> ConfigFile {
+        parse_file(Path::new("config.toml"), TOML).unwrap()
+    }
+
+    #[test]
+    fn test_default_profile() {
+        let config = toml_file().profile(None).unwrap().resolve().unwrap();
+
+        assert_eq!(config.folder_id, "base-folder");
+        assert_eq!(config.auth, Auth::api_key("base-key"));
+        assert_eq!(config.model.as_deref(), Some("yandexgpt-lite"));
+        assert_eq!(config.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(config.retry_policy.max_retries(), 5);
+        assert_eq!(config.endpoints.completion.as_deref(), Some("http://localhost/completion"));
+    }
+
+    #[test]
+    fn test_named_profile_overrides_base() {
+        let config = toml_file().profile(Some("prod")).unwrap().resolve().unwrap();
+
+        assert_eq!(config.folder_id, "prod-folder");
+        assert_eq!(config.auth, Auth::iam_token("prod-token"));
+        assert_eq!(config.model.as_deref(), Some("yandexgpt"));
+        assert_eq!(config.version.as_deref(), Some("rc"));
+        assert_eq!(config.timeout, Some(Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let result = toml_file().profile(Some("staging"));
+        assert!(matches!(result, Err(ConfigError::UnknownProfile(name)) if name == "staging"));
+    }
+
+    #[test]
+    fn test_yaml_file() {
+        let yaml = "folder_id: folder\niam_token: token\nretry:\n  max_retries: 0\n";
+        let config = parse_file(Path::new("config.yaml"), yaml)
+            .unwrap()
+            .profile(None)
+            .unwrap()
+            .resolve()
+            .unwrap();
+
+        assert_eq!(config.auth, Auth::iam_token("token"));
+        assert_eq!(config.retry_policy, RetryPolicy::none());
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let env = env_layer(|name| match name {
+            API_KEY_ENV => Some("env-key".to_string()),
+            MODEL_VERSION_ENV => Some("latest".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        let config = toml_file().profile(Some("prod")).unwrap().merge(env).resolve().unwrap();
+
+        assert_eq!(config.folder_id, "prod-folder");
+        assert_eq!(config.auth, Auth::api_key("env-key"));
+        assert_eq!(config.version.as_deref(), Some("latest"));
+    }
+
+    #[test]
+    fn test_invalid_env_timeout() {
+        let result = env_layer(|name| (name == TIMEOUT_ENV).then(|| "soon".to_string()));
+        assert!(matches!(result, Err(ConfigError::Invalid { field: TIMEOUT_ENV, .. })));
+    }
+
+    #[test]
+    fn test_env_connect_timeout() {
+        let layer = env_layer(|name| match name {
+            FOLDER_ID_ENV => Some("folder".to_string()),
+            API_KEY_ENV => Some("key".to_string()),
+            CONNECT_TIMEOUT_ENV => Some("5".to_string()),
+            _ => None,
+        });
+        assert_eq!(layer.unwrap().resolve().unwrap().connect_timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        for (name, text) in [
+            ("config.toml", "folder_id = \"f\"\nretry_polcy = 1\n"),
+            ("config.toml", "[retry]\nmax_retry = 1\n"),
+            ("config.toml", "[endpoints]\ncompletions = \"x\"\n"),
+            ("config.toml", "[profiles.prod]\nfolder = \"f\"\n"),
+            ("config.toml", "[profiles.prod.profiles.dev]\nfolder_id = \"f\"\n"),
+            ("config.yaml", "folder_id: f\ntimeout: 3\n"),
+        ] {
+            let result = parse_file(Path::new(name), text);
+            assert!(matches!(result, Err(ConfigError::Parse { .. })), "{text:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn test_missing_settings() {
+        let result = Layer::default().resolve();
+        assert!(matches!(result, Err(ConfigError::Missing("folder_id"))));
+
+        let layer = Layer { folder_id: Some("folder".to_string()), ..Layer::default() };
+        assert!(matches!(layer.resolve(), Err(ConfigError::Missing(_))));
+    }
+
+    #[test]
+    fn test_conflicting_auth_in_one_layer() {
+        let layer = Layer {
+            folder_id: Some("folder".to_string()),
+            api_key: Some(Secret::from("key")),
+            iam_token: Some(Secret::from("token")),
+            ..Layer::default()
+        };
+        assert!(matches!(layer.resolve(), Err(ConfigError::Invalid { field: "auth", .. })));
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+/// Errors returned while loading a Config
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Failed to read the config file
+    Io { path: PathBuf, source: std::io::Error },
+    /// The config file is not valid TOML/YAML or has unexpected fields
+    Parse { path: PathBuf, message: String },
+    /// The requested profile is not defined in the config file
+    UnknownProfile(String),
+    /// A required setting is not provided by the file or the environment
+    Missing(&'static str),
+    /// A setting has an invalid value
+    Invalid { field: &'static str, message: String },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io { path, source } => write!(f, "Failed to read {}: {}", path.display(), source),
+            ConfigError::Parse { path, message } => write!(f, "Invalid config {}: {}", path.display(), message),
+            ConfigError::UnknownProfile(name) => write!(f, "Unknown profile: {}", name),
+            ConfigError::Missing(field) => write!(f, "Missing setting: {}", field),
+            ConfigError::Invalid { field, message } => write!(f, "Invalid setting {}: {}", field, message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Returned instead of sending a request once the hard budget is spent
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetExceeded {
+    /// Amount spent so far
+    pub spent: f64,
+    /// Hard limit of the budget
+    pub limit: f64,
+}
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Budget exceeded: spent {:.2} of {:.2}", self.spent, self.limit)
+    }
+}
+
+impl std::error::Error for BudgetExceeded {}
+
+/// The credentials cannot be sent in an `Authorization` header, e.g. they contain a
+/// line break or other control characters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCredentials;
+
+impl std::fmt::Display for InvalidCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Credentials contain characters not allowed in an HTTP header")
+    }
+}
+
+impl std::error::Error for InvalidCredentials {}
@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::auth::Auth;
+use crate::error::InvalidCredentials;
+
+pub use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+pub use reqwest::{Method, StatusCode};
+
+/// An HTTP request with a fully buffered body
+///
+/// Clients build one per API call and pass it through their middleware before sending
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: HeaderMap,
+    pub body: Option<Vec<u8>>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        HttpRequest { method, url: url.into(), headers: HeaderMap::new(), body: None }
+    }
+
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::GET, url)
+    }
+
+    pub fn post(url: impl Into<String>) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    /// Sets a header, replacing previous values; invalid values are ignored
+    pub fn with_header(mut self, name: HeaderName, value: &str) -> Self {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            self.headers.insert(name, value);
+        }
+        self
+    }
+
+    /// Sets the `Authorization` header, marked sensitive so it is hidden in `Debug` output
+    ///
+    /// Fails instead of sending the request unauthenticated when the credentials are not a
+    /// valid header value
+    pub fn with_auth(mut self, auth: &Auth) -> Result<Self, InvalidCredentials> {
+        let mut value = HeaderValue::from_str(auth.header_value().expose()).map_err(|_| InvalidCredentials)?;
+        value.set_sensitive(true);
+        self.headers.insert(reqwest::header::AUTHORIZATION, value);
+        Ok(self)
+    }
+
+    /// Serializes `body` as the JSON body of the request
+    pub fn with_json<T: Serialize + ?Sized>(mut self, body: &T) -> Result<Self, serde_json::Error> {
+        self.body = Some(serde_json::to_vec(body)?);
+        self.headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        Ok(self)
+    }
+}
+
+/// An HTTP response with a fully buffered body
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        HttpResponse { status, headers: HeaderMap::new(), body: body.into() }
+    }
+
+    /// Parses the body as JSON
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+
+    /// Returns the body as text, replacing invalid UTF-8
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+
+    #[test]
+    fn test_request_builders() {
+        let request = HttpRequest::post("https://example.com")
+            .with_header(AUTHORIZATION, "Bearer t")
+            .with_json(&serde_json::json!({"a": 1}))
+            .unwrap();
+
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.headers[AUTHORIZATION], "Bearer t");
+        assert_eq!(request.headers[CONTENT_TYPE], "application/json");
+        assert_eq!(request.body.as_deref(), Some(&b"{\"a\":1}"[..]));
+    }
+
+    #[test]
+    fn test_auth_header_hidden_in_debug() {
+        let request = HttpRequest::get("https://example.com").with_auth(&Auth::api_key("AQVN-secret")).unwrap();
+        assert_eq!(request.headers[AUTHORIZATION], "Api-Key AQVN-secret");
+        assert!(!format!("{request:?}").contains("AQVN-secret"));
+    }
+
+    #[test]
+    fn test_invalid_auth_is_rejected() {
+        let request = HttpRequest::get("https://example.com").with_auth(&Auth::api_key("AQVN-secret\n"));
+        assert!(request.is_ok());
+
+        let request = HttpRequest::get("https://example.com").with_auth(&Auth::api_key("AQVN\nsecret"));
+        assert_eq!(request.unwrap_err(), InvalidCredentials);
+    }
+
+    #[test]
+    fn test_response_json_and_text() {
+        let response = HttpResponse { status: StatusCode::OK, headers: HeaderMap::new(), body: b"{\"a\":1}".to_vec() };
+        let value: serde_json::Value = response.json().unwrap();
+        assert_eq!(value["a"], 1);
+        assert_eq!(response.text(), "{\"a\":1}");
+    }
+}
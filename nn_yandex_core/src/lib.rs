@@ -0,0 +1,44 @@
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod config;
+pub mod error;
+pub mod http;
+pub mod middleware;
+pub mod retry;
+pub mod secret;
+pub mod telemetry;
+pub mod transport;
+pub mod usage;
+
+// Compile-time guarantee that public types can be shared between threads and tasks
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<auth::Auth>();
+    assert_send_sync::<auth::CredentialCell>();
+    #[cfg(feature = "blocking")]
+    assert_send_sync::<blocking::BlockingRuntime>();
+    assert_send_sync::<config::Config>();
+    assert_send_sync::<config::Endpoints>();
+    assert_send_sync::<error::ConfigError>();
+    assert_send_sync::<error::BudgetExceeded>();
+    assert_send_sync::<error::InvalidCredentials>();
+    assert_send_sync::<http::HttpRequest>();
+    assert_send_sync::<http::HttpResponse>();
+    assert_send_sync::<middleware::MiddlewareStack>();
+    assert_send_sync::<middleware::MiddlewareError>();
+    assert_send_sync::<retry::RetryPolicy>();
+    assert_send_sync::<secret::Secret>();
+    assert_send_sync::<telemetry::CallSpan>();
+    assert_send_sync::<telemetry::Endpoint>();
+    assert_send_sync::<transport::ReqwestTransport>();
+    assert_send_sync::<transport::InMemoryTransport>();
+    assert_send_sync::<transport::TransportError>();
+    assert_send_sync::<usage::UsageTracker>();
+    assert_send_sync::<usage::PriceTable>();
+    assert_send_sync::<usage::Budget>();
+    assert_send_sync::<usage::ModelUsage>();
+    assert_send_sync::<usage::TokenUsage>();
+    assert_send_sync::<usage::Mode>();
+};
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use crate::http::{HttpRequest, HttpResponse};
+
+/// Error returned by a middleware to stop a request from being sent
+pub type MiddlewareError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Hooks run around every HTTP call a client makes, retries included
+///
+/// Register with `with_middleware` on `TextGenerator` or `Art`. Middleware run in
+/// registration order before the request and in reverse order after it.
+pub trait Middleware: Send + Sync {
+    /// Called before the request is sent; may change it or reject it with an error
+    fn before_request(&self, request: &mut HttpRequest) -> Result<(), MiddlewareError> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Called with every received response, successful or not, before it is parsed
+    fn after_response(&self, request: &HttpRequest, response: &mut HttpResponse) {
+        let _ = (request, response);
+    }
+
+    /// Called when the call fails: on transport errors, API errors and unparsable responses
+    fn on_error(&self, request: &HttpRequest, error: &(dyn std::error::Error + 'static)) {
+        let _ = (request, error);
+    }
+}
+
+/// Ordered list of middleware registered on a client
+#[derive(Clone, Default)]
+pub struct MiddlewareStack {
+    layers: Vec<Arc<dyn Middleware>>,
+}
+
+impl MiddlewareStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, middleware: Arc<dyn Middleware>) {
+        self.layers.push(middleware);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Runs `before_request` of every middleware, stopping at the first error
+    pub fn before_request(&self, request: &mut HttpRequest) -> Result<(), MiddlewareError> {
+        self.layers.iter().try_for_each(|layer| layer.before_request(request))
+    }
+
+    pub fn after_response(&self, request: &HttpRequest, response: &mut HttpResponse) {
+        for layer in self.layers.iter().rev() {
+            layer.after_response(request, response);
+        }
+    }
+
+    pub fn on_error(&self, request: &HttpRequest, error: &(dyn std::error::Error + 'static)) {
+        for layer in self.layers.iter().rev() {
+            layer.on_error(request, error);
+        }
+    }
+}
+
+impl std::fmt::Debug for MiddlewareStack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MiddlewareStack").field("layers", &self.layers.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{HeaderMap, HeaderName, StatusCode};
+    use std::sync::Mutex;
+
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Middleware for Recorder {
+        fn before_request(&self, request: &mut HttpRequest) -> Result<(), MiddlewareError> {
+            self.log.lock().unwrap().push(format!("before {}", self.name));
+            *request = request.clone().with_header(HeaderName::from_static("x-trace"), self.name);
+            Ok(())
+        }
+
+        fn after_response(&self, _: &HttpRequest, response: &mut HttpResponse) {
+            self.log.lock().unwrap().push(format!("after {}", self.name));
+            response.body.extend_from_slice(self.name.as_bytes());
+        }
+    }
+
+    struct Deny;
+
+    impl Middleware for Deny {
+        fn before_request(&self, _: &mut HttpRequest) -> Result<(), MiddlewareError> {
+            Err("denied".into())
+        }
+    }
+
+    #[test]
+    fn test_order_and_mutation() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut stack = MiddlewareStack::new();
+        stack.push(Arc::new(Recorder { name: "a", log: log.clone() }));
+        stack.push(Arc::new(Recorder { name: "b", log: log.clone() }));
+
+        let mut request = HttpRequest::get("https://example.com");
+        stack.before_request(&mut request).unwrap();
+        let mut response = HttpResponse { status: StatusCode::OK, headers: HeaderMap::new(), body: Vec::new() };
+        stack.after_response(&request, &mut response);
+
+        assert_eq!(request.headers["x-trace"], "b");
+        assert_eq!(response.body, b"ba");
+        assert_eq!(*log.lock().unwrap(), ["before a", "before b", "after b", "after a"]);
+    }
+
+    #[test]
+    fn test_rejection_stops_chain() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut stack = MiddlewareStack::new();
+        stack.push(Arc::new(Deny));
+        stack.push(Arc::new(Recorder { name: "a", log: log.clone() }));
+
+        let error = stack.before_request(&mut HttpRequest::get("https://example.com")).unwrap_err();
+        assert_eq!(error.to_string(), "denied");
+        assert!(log.lock().unwrap().is_empty());
+    }
+}
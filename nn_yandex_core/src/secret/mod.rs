@@ -0,0 +1,112 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Deserializer};
+use zeroize::Zeroize;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Authorization schemes whose credentials are masked by `redact`
+const SCHEMES: [&str; 2] = ["Api-Key ", "Bearer "];
+
+/// A credential that never appears in `Debug` or `Display` output and is
+/// wiped from memory when dropped
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    /// Returns the secret value; keep the result out of logs
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Secret({REDACTED})")
+    }
+}
+
+impl std::fmt::Display for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret(value.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Secret)
+    }
+}
+
+/// Masks credentials following `Api-Key ` or `Bearer ` in `text`, e.g. in error
+/// messages that echo request headers
+pub fn redact(text: &str) -> Cow<'_, str> {
+    if !SCHEMES.iter().any(|scheme| text.contains(scheme)) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some((index, scheme)) = SCHEMES
+        .iter()
+        .filter_map(|scheme| rest.find(scheme).map(|index| (index, scheme)))
+        .min_by_key(|(index, _)| *index)
+    {
+        let start = index + scheme.len();
+        result.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ',' | ';' | ')' | '}'))
+            .map_or(rest.len(), |offset| start + offset);
+        if end > start {
+            result.push_str(REDACTED);
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let secret = Secret::new("AQVN-secret");
+        assert_eq!(format!("{secret}"), "[REDACTED]");
+        assert_eq!(format!("{secret:?}"), "Secret([REDACTED])");
+        assert_eq!(secret.expose(), "AQVN-secret");
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(
+            redact(r#"headers: {"authorization": "Api-Key AQVN123"}, retry with Bearer t1.abc"#),
+            r#"headers: {"authorization": "Api-Key [REDACTED]"}, retry with Bearer [REDACTED]"#
+        );
+        assert!(matches!(redact("nothing to hide"), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,447 @@
+//! Instrumentation of API calls
+//!
+//! With the `tracing` feature every call runs inside an INFO span named after the
+//! endpoint (`complete`, `generate_image`, `check_operation`, `cancel_operation`) with
+//! the fields `model_uri`, `operation_id`, `http.status`, `latency_ms`, `retries`,
+//! `input_tokens`, `completion_tokens`, `reasoning_tokens` and `error`.
+//! Prompts and responses are emitted as TRACE events only when the client opted in
+//! with `with_trace_content(true)`.
+//!
+//! With the `metrics` feature calls are reported through the `metrics` facade:
+//!
+//! * `nn_yandex_requests_total` (counter; `endpoint`, `model`, `outcome` = `ok`/`error`)
+//! * `nn_yandex_request_duration_seconds` (histogram; `endpoint`, `model`)
+//! * `nn_yandex_tokens_total` (counter; `model`, `kind` = `input`/`completion`/`reasoning`)
+//! * `nn_yandex_errors_total` (counter; `endpoint`, `code`)
+//! * `nn_yandex_image_operation_duration_seconds` (histogram of `modified_at - created_at`
+//!   of finished image operations)
+//!
+//! The `model` label is `<name>/<version>` of the model URI (`yandexgpt/latest`); the
+//! folder ID is left out so it does not end up in metrics backends.
+//!
+//! Without either feature `CallSpan` does nothing.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use std::time::Instant;
+
+use crate::usage::TokenUsage;
+
+pub const REQUESTS_TOTAL: &str = "nn_yandex_requests_total";
+pub const REQUEST_DURATION_SECONDS: &str = "nn_yandex_request_duration_seconds";
+pub const TOKENS_TOTAL: &str = "nn_yandex_tokens_total";
+pub const ERRORS_TOTAL: &str = "nn_yandex_errors_total";
+pub const IMAGE_OPERATION_DURATION_SECONDS: &str = "nn_yandex_image_operation_duration_seconds";
+
+/// Model label used when the model of a call is unknown (operation endpoints)
+#[cfg(feature = "metrics")]
+const UNKNOWN_MODEL: &str = "unknown";
+
+/// API endpoint a call is made to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    Complete,
+    GenerateImage,
+    CheckOperation,
+    CancelOperation,
+}
+
+impl Endpoint {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Endpoint::Complete => "complete",
+            Endpoint::GenerateImage => "generate_image",
+            Endpoint::CheckOperation => "check_operation",
+            Endpoint::CancelOperation => "cancel_operation",
+        }
+    }
+}
+
+/// Short machine-readable error code used as a metric label
+pub trait ErrorCode {
+    fn error_code(&self) -> String;
+}
+
+/// Telemetry of a single API call, retries included
+#[derive(Clone)]
+pub struct CallSpan {
+    #[cfg(feature = "metrics")]
+    endpoint: Endpoint,
+    #[cfg(feature = "metrics")]
+    model: String,
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    started: Instant,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    trace_content: bool,
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! call_span {
+    ($name:literal) => {
+        tracing::info_span!(
+            $name,
+            model_uri = tracing::field::Empty,
+            operation_id = tracing::field::Empty,
+            http.status = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            retries = tracing::field::Empty,
+            input_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+            reasoning_tokens = tracing::field::Empty,
+            error = tracing::field::Empty,
+        )
+    };
+}
+
+impl CallSpan {
+    /// Starts a call; `trace_content` allows prompts and responses to be logged
+    pub fn new(endpoint: Endpoint, trace_content: bool) -> Self {
+        #[cfg(not(feature = "tracing"))]
+        let _ = trace_content;
+        #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+        let _ = endpoint;
+        CallSpan {
+            #[cfg(feature = "metrics")]
+            endpoint,
+            #[cfg(feature = "metrics")]
+            model: UNKNOWN_MODEL.to_string(),
+            #[cfg(any(feature = "tracing", feature = "metrics"))]
+            started: Instant::now(),
+            #[cfg(feature = "tracing")]
+            span: match endpoint {
+                Endpoint::Complete => call_span!("complete"),
+                Endpoint::GenerateImage => call_span!("generate_image"),
+                Endpoint::CheckOperation => call_span!("check_operation"),
+                Endpoint::CancelOperation => call_span!("cancel_operation"),
+            },
+            #[cfg(feature = "tracing")]
+            trace_content,
+        }
+    }
+
+    /// Sets the model the call is made to
+    pub fn with_model_uri(mut self, model_uri: &str) -> Self {
+        self.set_model_uri(model_uri);
+        self
+    }
+
+    fn set_model_uri(&mut self, model_uri: &str) {
+        #[cfg(feature = "tracing")]
+        self.span.record("model_uri", model_uri);
+        #[cfg(feature = "metrics")]
+        {
+            self.model = model_label(model_uri).to_string();
+        }
+        #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+        let _ = model_uri;
+    }
+
+    pub fn record_operation_id(&self, operation_id: &str) {
+        #[cfg(feature = "tracing")]
+        self.span.record("operation_id", operation_id);
+        #[cfg(not(feature = "tracing"))]
+        let _ = operation_id;
+    }
+
+    pub fn record_status(&self, status: u16) {
+        #[cfg(feature = "tracing")]
+        self.span.record("http.status", status);
+        #[cfg(not(feature = "tracing"))]
+        let _ = status;
+    }
+
+    pub fn record_retries(&self, retries: u32) {
+        #[cfg(feature = "tracing")]
+        self.span.record("retries", retries);
+        #[cfg(not(feature = "tracing"))]
+        let _ = retries;
+    }
+
+    pub fn record_tokens(&self, tokens: TokenUsage) {
+        #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+        let _ = tokens;
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("input_tokens", tokens.input_text_tokens);
+            self.span.record("completion_tokens", tokens.completion_tokens);
+            self.span.record("reasoning_tokens", tokens.reasoning_tokens);
+        }
+        #[cfg(feature = "metrics")]
+        for (kind, count) in [
+            ("input", tokens.input_text_tokens),
+            ("completion", tokens.completion_tokens),
+            ("reasoning", tokens.reasoning_tokens),
+        ] {
+            metrics::counter!(TOKENS_TOTAL, "model" => self.model.clone(), "kind" => kind).increment(count);
+        }
+    }
+
+    /// Records how long a finished image operation took on the server
+    pub fn record_operation_duration(&self, duration: Duration) {
+        #[cfg(feature = "metrics")]
+        metrics::histogram!(IMAGE_OPERATION_DURATION_SECONDS).record(duration.as_secs_f64());
+        #[cfg(not(feature = "metrics"))]
+        let _ = duration;
+    }
+
+    /// Emits `content` as a TRACE event if the client opted in; `content` is not
+    /// evaluated otherwise
+    pub fn trace_content(&self, kind: &'static str, content: impl FnOnce() -> String) {
+        #[cfg(feature = "tracing")]
+        if self.trace_content && tracing::enabled!(tracing::Level::TRACE) {
+            tracing::trace!(parent: &self.span, kind, content = %content());
+        }
+        #[cfg(not(feature = "tracing"))]
+        let _ = (kind, content);
+    }
+
+    /// Runs the call inside the span and records its latency and outcome
+    pub fn run<F, Fut, T, E>(self, call: F) -> impl Future<Output = Result<T, E>>
+    where
+        F: FnOnce(CallSpan) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Display + ErrorCode,
+    {
+        #[cfg(feature = "tracing")]
+        let span = self.span.clone();
+
+        let call = async move {
+            let result = call(self.clone()).await;
+            self.finish(&result);
+            result
+        };
+
+        #[cfg(feature = "tracing")]
+        let call = tracing::Instrument::instrument(call, span);
+
+        call
+    }
+
+    fn finish<T, E: Display + ErrorCode>(&self, result: &Result<T, E>) {
+        #[cfg(any(feature = "tracing", feature = "metrics"))]
+        let elapsed = self.started.elapsed();
+        #[cfg(not(any(feature = "tracing", feature = "metrics")))]
+        let _ = result;
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("latency_ms", elapsed.as_millis() as u64);
+            if let Err(e) = result {
+                self.span.record("error", tracing::field::display(e));
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            let endpoint = self.endpoint.as_str();
+            let outcome = if result.is_ok() { "ok" } else { "error" };
+            metrics::counter!(REQUESTS_TOTAL, "endpoint" => endpoint, "model" => self.model.clone(), "outcome" => outcome)
+                .increment(1);
+            metrics::histogram!(REQUEST_DURATION_SECONDS, "endpoint" => endpoint, "model" => self.model.clone())
+                .record(elapsed.as_secs_f64());
+            if let Err(e) = result {
+                metrics::counter!(ERRORS_TOTAL, "endpoint" => endpoint, "code" => e.error_code()).increment(1);
+            }
+        }
+    }
+}
+
+/// Returns `<name>[/<version>]` from `scheme://<folder>/<name>[/<version>]`
+#[cfg(feature = "metrics")]
+fn model_label(model_uri: &str) -> &str {
+    model_uri
+        .split_once("://")
+        .and_then(|(_, path)| path.split_once('/'))
+        .map_or(UNKNOWN_MODEL, |(_, model)| model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestError;
+
+    impl Display for TestError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "failed")
+        }
+    }
+
+    impl ErrorCode for TestError {
+        fn error_code(&self) -> String {
+            "UNAVAILABLE".to_string()
+        }
+    }
+
+    /// Polls a future that never waits to completion
+    fn block_on<F: Future>(future: F) -> F::Output {
+        use std::pin::pin;
+        use std::task::{Context, Poll, Waker};
+
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_model_label_omits_folder() {
+        assert_eq!(model_label("gpt://b1gfolder/yandexgpt/latest"), "yandexgpt/latest");
+        assert_eq!(model_label("art://b1gfolder/yandex-art"), "yandex-art");
+        assert_eq!(model_label("yandexgpt"), UNKNOWN_MODEL);
+    }
+
+    #[test]
+    fn test_content_not_evaluated_without_opt_in() {
+        let span = CallSpan::new(Endpoint::Complete, false);
+        span.trace_content("prompt", || panic!("content must not be rendered"));
+    }
+
+    #[test]
+    fn test_run_returns_call_result() {
+        let span = CallSpan::new(Endpoint::CheckOperation, false);
+        let result: Result<u32, TestError> = block_on(span.run(|span| async move {
+            span.record_retries(2);
+            Err(TestError)
+        }));
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_span_fields_recorded() {
+        use std::collections::HashMap;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata};
+
+        type Spans = Arc<Mutex<HashMap<u64, (&'static str, HashMap<String, String>)>>>;
+
+        /// Keeps the name and recorded fields of every span
+        #[derive(Default)]
+        struct Capture {
+            next_id: AtomicU64,
+            spans: Spans,
+        }
+
+        struct Fields<'a>(&'a mut HashMap<String, String>);
+
+        impl Visit for Fields<'_> {
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.insert(field.name().to_string(), value.to_string());
+            }
+
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.insert(field.name().to_string(), format!("{value:?}"));
+            }
+        }
+
+        impl tracing::Subscriber for Capture {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+                let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+                let mut fields = HashMap::new();
+                attributes.record(&mut Fields(&mut fields));
+                self.spans.lock().unwrap().insert(id, (attributes.metadata().name(), fields));
+                Id::from_u64(id)
+            }
+
+            fn record(&self, span: &Id, values: &Record<'_>) {
+                if let Some((_, fields)) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+                    values.record(&mut Fields(fields));
+                }
+            }
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {}
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let capture = Capture::default();
+        let spans = capture.spans.clone();
+        tracing::subscriber::with_default(capture, || {
+            let span = CallSpan::new(Endpoint::GenerateImage, false).with_model_uri("art://f/yandex-art/latest");
+            let _: Result<(), TestError> = block_on(span.run(|span| async move {
+                span.record_operation_id("op-1");
+                span.record_status(503);
+                span.record_retries(2);
+                Err(TestError)
+            }));
+        });
+
+        let spans = spans.lock().unwrap();
+        let (_, fields) = spans.values().find(|(name, _)| *name == "generate_image").expect("span was not created");
+        assert_eq!(fields["model_uri"], "art://f/yandex-art/latest");
+        assert_eq!(fields["operation_id"], "op-1");
+        assert_eq!(fields["http.status"], "503");
+        assert_eq!(fields["retries"], "2");
+        assert_eq!(fields["error"], "failed");
+        assert!(fields.contains_key("latency_ms"));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_metrics_recorded() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        metrics::with_local_recorder(&recorder, || {
+            let span = CallSpan::new(Endpoint::Complete, false).with_model_uri("gpt://f/yandexgpt/latest");
+            let _: Result<(), TestError> = block_on(span.run(|span| async move {
+                span.record_tokens(TokenUsage { input_text_tokens: 10, completion_tokens: 20, reasoning_tokens: 5 });
+                Ok(())
+            }));
+            let span = CallSpan::new(Endpoint::CheckOperation, false);
+            let _: Result<(), TestError> = block_on(span.run(|span| async move {
+                span.record_operation_duration(Duration::from_secs(3));
+                Err(TestError)
+            }));
+        });
+
+        type Metric = (String, Vec<(String, String)>, DebugValue);
+
+        let metrics: Vec<Metric> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                let key = key.key();
+                let labels = key.labels().map(|l| (l.key().to_string(), l.value().to_string())).collect();
+                (key.name().to_string(), labels, value)
+            })
+            .collect();
+        let find = |name: &str, label: (&str, &str)| {
+            metrics
+                .iter()
+                .find(|(n, labels, _)| {
+                    n == name && labels.iter().any(|(k, v)| k == label.0 && v == label.1)
+                })
+                .map(|(_, _, value)| value)
+        };
+
+        assert_eq!(find(TOKENS_TOTAL, ("kind", "completion")), Some(&DebugValue::Counter(20)));
+        assert_eq!(find(REQUESTS_TOTAL, ("outcome", "ok")), Some(&DebugValue::Counter(1)));
+        assert_eq!(find(REQUESTS_TOTAL, ("model", "yandexgpt/latest")), Some(&DebugValue::Counter(1)));
+        assert!(metrics.iter().all(|(_, labels, _)| labels.iter().all(|(_, v)| !v.contains("://"))));
+        assert_eq!(find(REQUESTS_TOTAL, ("model", UNKNOWN_MODEL)), Some(&DebugValue::Counter(1)));
+        assert_eq!(find(ERRORS_TOTAL, ("code", "UNAVAILABLE")), Some(&DebugValue::Counter(1)));
+        assert!(matches!(find(REQUEST_DURATION_SECONDS, ("endpoint", "complete")), Some(DebugValue::Histogram(v)) if v.len() == 1));
+        assert!(metrics.iter().any(|(name, _, value)| name == IMAGE_OPERATION_DURATION_SECONDS
+            && matches!(value, DebugValue::Histogram(v) if v[0].into_inner() == 3.0)));
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+use bytes::Bytes;
+use futures_core::Stream;
+use futures_util::StreamExt;
+
+use crate::http::{HeaderMap, HttpRequest, HttpResponse, StatusCode};
+
+/// Boxed future returned by `HttpTransport`
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Response body delivered in chunks
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, TransportError>> + Send>>;
+
+/// Category of a transport failure, used to decide whether to retry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// The connection could not be established
+    Connect,
+    /// The request or response timed out
+    Timeout,
+    /// Any other failure
+    Other,
+}
+
+/// Failure to exchange a request with the server; no response was received
+#[derive(Debug)]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl TransportError {
+    pub fn new(kind: TransportErrorKind, source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        TransportError { kind, source: source.into() }
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.kind == TransportErrorKind::Timeout
+    }
+
+    pub fn is_connect(&self) -> bool {
+        self.kind == TransportErrorKind::Connect
+    }
+
+    /// Returns the underlying error, e.g. to downcast it to `reqwest::Error`
+    pub fn get_ref(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        &*self.source
+    }
+}
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(e: reqwest::Error) -> Self {
+        let kind = if e.is_timeout() {
+            TransportErrorKind::Timeout
+        } else if e.is_connect() {
+            TransportErrorKind::Connect
+        } else {
+            TransportErrorKind::Other
+        };
+        TransportError::new(kind, e)
+    }
+}
+
+/// Response whose body is read as a stream of chunks
+pub struct StreamingResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: ByteStream,
+}
+
+/// Sends HTTP requests for the clients
+///
+/// The default is `ReqwestTransport`; set another one with `with_transport` to use a
+/// different HTTP stack, record traffic or answer requests in memory in tests.
+///
+/// A custom transport does not make the clients runtime-agnostic: retry backoff, operation
+/// polling and the batch runner's pacing wait with `tokio::time`, so those calls must run
+/// on a Tokio runtime with the time driver enabled.
+pub trait HttpTransport: Send + Sync {
+    /// Sends `request` and reads the whole response body
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<HttpResponse, TransportError>>;
+
+    /// Sends `request` and returns the body as a stream
+    ///
+    /// The default implementation reads the whole body with `send` and yields it as one chunk
+    fn send_streaming<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+    ) -> BoxFuture<'a, Result<StreamingResponse, TransportError>> {
+        Box::pin(async move {
+            let response = self.send(request).await?;
+            let body = Bytes::from(response.body);
+            Ok(StreamingResponse {
+                status: response.status,
+                headers: response.headers,
+                body: Box::pin(futures_util::stream::once(async move { Ok(body) })),
+            })
+        })
+    }
+}
+
+/// Transport backed by `reqwest::Client`
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        ReqwestTransport { client }
+    }
+
+    async fn execute(&self, request: &HttpRequest) -> Result<reqwest::Response, TransportError> {
+        let mut builder = self
+            .client
+            .request(request.method.clone(), &request.url)
+            .headers(request.headers.clone());
+        if let Some(body) = &request.body {
+            builder = builder.body(body.clone());
+        }
+        Ok(builder.send().await?)
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<HttpResponse, TransportError>> {
+        Box::pin(async move {
+            let response = self.execute(request).await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?.to_vec();
+            Ok(HttpResponse { status, headers, body })
+        })
+    }
+
+    fn send_streaming<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+    ) -> BoxFuture<'a, Result<StreamingResponse, TransportError>> {
+        Box::pin(async move {
+            let response = self.execute(request).await?;
+            Ok(StreamingResponse {
+                status: response.status(),
+                headers: response.headers().clone(),
+                body: Box::pin(response.bytes_stream().map(|chunk| chunk.map_err(TransportError::from))),
+            })
+        })
+    }
+}
+
+type Handler = Box<dyn Fn(&HttpRequest) -> Result<HttpResponse, TransportError> + Send + Sync>;
+
+/// Transport that answers requests in memory without opening sockets
+///
+/// Responses come from a handler or, with `from_responses`, from a queue. Every request
+/// is recorded and available through `requests`.
+pub struct InMemoryTransport {
+    handler: Handler,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl InMemoryTransport {
+    /// Answers every request with `handler`
+    pub fn new(handler: impl Fn(&HttpRequest) -> Result<HttpResponse, TransportError> + Send + Sync + 'static) -> Self {
+        InMemoryTransport { handler: Box::new(handler), requests: Mutex::new(Vec::new()) }
+    }
+
+    /// Answers requests with `responses` in order; requests after the last one fail
+    pub fn from_responses(responses: impl IntoIterator<Item = Result<HttpResponse, TransportError>>) -> Self {
+        let queue = Mutex::new(responses.into_iter().collect::<VecDeque<_>>());
+        Self::new(move |_| {
+            queue
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .pop_front()
+                .unwrap_or_else(|| Err(TransportError::new(TransportErrorKind::Other, "no more responses")))
+        })
+    }
+
+    /// Returns the requests sent so far
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+impl HttpTransport for InMemoryTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<HttpResponse, TransportError>> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(request.clone());
+        let response = (self.handler)(request);
+        Box::pin(async move { response })
+    }
+}
+
+impl<T: HttpTransport + ?Sized> HttpTransport for std::sync::Arc<T> {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> BoxFuture<'a, Result<HttpResponse, TransportError>> {
+        (**self).send(request)
+    }
+
+    fn send_streaming<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+    ) -> BoxFuture<'a, Result<StreamingResponse, TransportError>> {
+        (**self).send_streaming(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::task::{Context, Poll, Waker};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn test_in_memory_queue_and_recording() {
+        let transport = InMemoryTransport::from_responses([
+            Ok(HttpResponse::new(StatusCode::OK, "first")),
+            Err(TransportError::new(TransportErrorKind::Timeout, "slow")),
+        ]);
+        let request = HttpRequest::get("https://example.com/a");
+
+        assert_eq!(block_on(transport.send(&request)).unwrap().body, b"first");
+        assert!(block_on(transport.send(&request)).unwrap_err().is_timeout());
+        assert_eq!(block_on(transport.send(&request)).unwrap_err().kind(), TransportErrorKind::Other);
+        assert_eq!(transport.requests().len(), 3);
+        assert_eq!(transport.requests()[0].url, "https://example.com/a");
+    }
+
+    #[test]
+    fn test_default_streaming_yields_whole_body() {
+        let transport = InMemoryTransport::new(|_| Ok(HttpResponse::new(StatusCode::OK, "body")));
+        let request = HttpRequest::get("https://example.com");
+        let mut response = block_on(transport.send_streaming(&request)).unwrap();
+
+        let chunks: Vec<Bytes> = block_on(async {
+            let mut chunks = Vec::new();
+            while let Some(chunk) = response.body.next().await {
+                chunks.push(chunk.unwrap());
+            }
+            chunks
+        });
+        assert_eq!(chunks, [Bytes::from("body")]);
+    }
+}
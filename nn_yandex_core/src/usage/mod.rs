@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+
+use crate::error::BudgetExceeded;
+
+/// Token counts of a single completion
+///
+/// `reasoning_tokens` are part of `completion_tokens`; they are tracked separately
+/// but not billed twice
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_text_tokens: u64,
+    pub completion_tokens: u64,
+    pub reasoning_tokens: u64,
+}
+
+/// How a completion was requested; asynchronous and batch requests are billed at a discount
+///
+/// Only text is discounted: image generations are always asynchronous and cost
+/// `PriceTable::image_price` as is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Sync,
+    Async,
+}
+
+/// Aggregated usage and cost of one model URI
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModelUsage {
+    /// Number of API calls: one per completion and one per started image generation
+    /// request, whatever number of images it asks for
+    pub requests: u64,
+    pub input_text_tokens: u64,
+    pub completion_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub image_generations: u64,
+    pub cost: f64,
+}
+
+/// Prices used to turn usage into cost
+///
+/// Text is billed in units: tokens times the model's multiplier, priced per 1000 units.
+/// Multipliers are looked up by full model URI first and then by model name
+/// (`yandexgpt` in `gpt://<folder>/yandexgpt/latest`); unknown models use 1.0.
+///
+/// ```toml
+/// price_per_1k_units = 0.20
+/// image_price = 2.00
+/// async_discount = 0.5
+///
+/// [multipliers]
+/// yandexgpt = 6.0
+/// yandexgpt-lite = 1.0
+/// ```
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PriceTable {
+    #[serde(default)]
+    pub price_per_1k_units: f64,
+    #[serde(default)]
+    pub image_price: f64,
+    /// Fraction of the price taken off asynchronous requests (0.5 = half price)
+    #[serde(default)]
+    pub async_discount: f64,
+    #[serde(default)]
+    pub multipliers: HashMap<String, f64>,
+}
+
+impl PriceTable {
+    pub fn new(price_per_1k_units: f64, image_price: f64) -> Self {
+        PriceTable { price_per_1k_units, image_price, ..Default::default() }
+    }
+
+    pub fn with_multiplier(mut self, model: &str, multiplier: f64) -> Self {
+        self.multipliers.insert(model.to_string(), multiplier);
+        self
+    }
+
+    pub fn with_async_discount(mut self, async_discount: f64) -> Self {
+        self.async_discount = async_discount;
+        self
+    }
+
+    fn multiplier(&self, model_uri: &str) -> f64 {
+        self.multipliers
+            .get(model_uri)
+            .or_else(|| model_name(model_uri).and_then(|name| self.multipliers.get(name)))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Cost of a completion with the given token counts
+    pub fn text_cost(&self, model_uri: &str, tokens: TokenUsage, mode: Mode) -> f64 {
+        let units = (tokens.input_text_tokens + tokens.completion_tokens) as f64 * self.multiplier(model_uri);
+        let cost = units / 1000.0 * self.price_per_1k_units;
+        match mode {
+            Mode::Sync => cost,
+            Mode::Async => cost * (1.0 - self.async_discount),
+        }
+    }
+}
+
+/// Returns `name` from `scheme://<folder>/<name>[/<version>]`
+fn model_name(model_uri: &str) -> Option<&str> {
+    let (_, path) = model_uri.split_once("://")?;
+    path.split('/').nth(1)
+}
+
+/// Spending limits
+///
+/// Crossing the soft limit invokes the callback set with `UsageTracker::on_soft_limit` once;
+/// after the hard limit is reached every call is rejected with `BudgetExceeded`.
+///
+/// Spending has to stay below the hard limit: a call is rejected once the amount spent
+/// is equal to the limit or above it, and a reservation is rejected if it would bring
+/// spending to the limit or past it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Budget {
+    pub soft: Option<f64>,
+    pub hard: Option<f64>,
+}
+
+#[derive(Default)]
+struct State {
+    models: HashMap<String, ModelUsage>,
+    spent: f64,
+    soft_limit_reported: bool,
+}
+
+type SoftLimitCallback = Box<dyn Fn(f64) + Send + Sync>;
+
+/// Thread-safe accumulator of usage and cost, shared between clients via `Arc`
+///
+/// Clients call `check` before sending a request and record usage after a successful one.
+/// The two steps are not atomic: concurrent requests can all pass `check` before any of
+/// them is recorded, and requests already in flight when the hard limit is reached still
+/// complete, so the final spend may exceed the limit by the cost of those requests.
+pub struct UsageTracker {
+    prices: PriceTable,
+    budget: Budget,
+    on_soft_limit: Option<SoftLimitCallback>,
+    state: Mutex<State>,
+}
+
+impl UsageTracker {
+    pub fn new(prices: PriceTable) -> Self {
+        UsageTracker { prices, budget: Budget::default(), on_soft_limit: None, state: Mutex::new(State::default()) }
+    }
+
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Sets a callback invoked with the amount spent when the soft limit is first crossed
+    pub fn on_soft_limit(mut self, callback: impl Fn(f64) + Send + Sync + 'static) -> Self {
+        self.on_soft_limit = Some(Box::new(callback));
+        self
+    }
+
+    /// Fails once the hard limit is reached
+    ///
+    /// This only looks at what has been recorded so far; see the type docs for how
+    /// concurrent requests can overshoot the limit.
+    pub fn check(&self) -> Result<(), BudgetExceeded> {
+        self.check_cost(self.lock().spent, 0.0)
+    }
+
+    /// Fails if spending `cost` on top of `spent` would reach the hard limit; a zero cost
+    /// fails when the limit is already reached
+    fn check_cost(&self, spent: f64, cost: f64) -> Result<(), BudgetExceeded> {
+        match self.budget.hard {
+            Some(limit) if spent + cost >= limit => Err(BudgetExceeded { spent, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records a completed text request
+    pub fn record_text(&self, model_uri: &str, tokens: TokenUsage, mode: Mode) {
+        let cost = self.prices.text_cost(model_uri, tokens, mode);
+        self.record(model_uri, cost, |usage| {
+            usage.requests += 1;
+            usage.input_text_tokens += tokens.input_text_tokens;
+            usage.completion_tokens += tokens.completion_tokens;
+            usage.reasoning_tokens += tokens.reasoning_tokens;
+        });
+    }
+
+    /// Records one API call that started `count` image generations
+    pub fn record_images(&self, model_uri: &str, count: u64) {
+        let cost = self.prices.image_price * count as f64;
+        self.record(model_uri, cost, |usage| {
+            usage.requests += 1;
+            usage.image_generations += count;
+        });
+    }
+
+    /// Records `count` image generations of one image each (`count` API calls) before they
+    /// are started, if their cost keeps spending below the hard limit
+    ///
+    /// The check and the record happen at once, so concurrent reservations cannot overshoot
+    /// the limit. Generations that are not started after all are given back with
+    /// `release_images`.
+    pub fn reserve_images(&self, model_uri: &str, count: u64) -> Result<(), BudgetExceeded> {
+        let cost = self.prices.image_price * count as f64;
+        let crossed_soft_limit = {
+            let mut state = self.lock();
+            self.check_cost(state.spent, cost)?;
+            self.apply(&mut state, model_uri, cost, |usage| {
+                usage.requests += count;
+                usage.image_generations += count;
+            })
+        };
+        self.notify_soft_limit(crossed_soft_limit);
+        Ok(())
+    }
+
+    /// Gives back image generations reserved with `reserve_images` that were not started
+    pub fn release_images(&self, model_uri: &str, count: u64) {
+        let cost = self.prices.image_price * count as f64;
+        let mut state = self.lock();
+        if let Some(usage) = state.models.get_mut(model_uri) {
+            usage.requests = usage.requests.saturating_sub(count);
+            usage.image_generations = usage.image_generations.saturating_sub(count);
+            usage.cost -= cost;
+            state.spent -= cost;
+        }
+    }
+
+    fn record(&self, model_uri: &str, cost: f64, update: impl FnOnce(&mut ModelUsage)) {
+        let crossed_soft_limit = self.apply(&mut self.lock(), model_uri, cost, update);
+        self.notify_soft_limit(crossed_soft_limit);
+    }
+
+    /// Adds `cost` to the usage of `model_uri`; returns the amount spent if this crossed
+    /// the soft limit
+    fn apply(&self, state: &mut State, model_uri: &str, cost: f64, update: impl FnOnce(&mut ModelUsage)) -> Option<f64> {
+        let usage = state.models.entry(model_uri.to_string()).or_default();
+        usage.cost += cost;
+        update(usage);
+        state.spent += cost;
+
+        let crossed = !state.soft_limit_reported && self.budget.soft.is_some_and(|soft| state.spent >= soft);
+        state.soft_limit_reported |= crossed;
+        crossed.then_some(state.spent)
+    }
+
+    /// Invokes the soft limit callback outside the lock
+    fn notify_soft_limit(&self, crossed_soft_limit: Option<f64>) {
+        if let (Some(spent), Some(callback)) = (crossed_soft_limit, &self.on_soft_limit) {
+            callback(spent);
+        }
+    }
+
+    /// Returns a snapshot of usage per model URI
+    pub fn usage(&self) -> HashMap<String, ModelUsage> {
+        self.lock().models.clone()
+    }
+
+    /// Returns the total amount spent
+    pub fn spent(&self) -> f64 {
+        self.lock().spent
+    }
+
+    /// `true` once spending has reached the soft limit
+    pub fn is_soft_limit_exceeded(&self) -> bool {
+        self.budget.soft.is_some_and(|soft| self.spent() >= soft)
+    }
+
+    /// Clears all recorded usage, e.g. at the start of a new billing period
+    pub fn reset(&self) {
+        *self.lock() = State::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const PRO: &str = "gpt://folder/yandexgpt/latest";
+    const LITE: &str = "gpt://folder/yandexgpt-lite/latest";
+
+    fn tokens(input: u64, completion: u64) -> TokenUsage {
+        TokenUsage { input_text_tokens: input, completion_tokens: completion, reasoning_tokens: 0 }
+    }
+
+    #[test]
+    fn test_text_cost_with_multiplier_and_discount() {
+        let prices = PriceTable::new(0.2, 0.0).with_multiplier("yandexgpt", 6.0).with_async_discount(0.5);
+
+        assert!((prices.text_cost(PRO, tokens(500, 500), Mode::Sync) - 1.2).abs() < 1e-9);
+        assert!((prices.text_cost(PRO, tokens(500, 500), Mode::Async) - 0.6).abs() < 1e-9);
+        assert!((prices.text_cost(LITE, tokens(500, 500), Mode::Sync) - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_multiplier_by_full_uri_wins() {
+        let prices = PriceTable::new(1.0, 0.0)
+            .with_multiplier("yandexgpt", 6.0)
+            .with_multiplier(PRO, 2.0);
+        assert!((prices.text_cost(PRO, tokens(1000, 0), Mode::Sync) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_aggregates_per_model() {
+        let tracker = UsageTracker::new(PriceTable::new(1.0, 3.0));
+        tracker.record_text(PRO, TokenUsage { input_text_tokens: 10, completion_tokens: 20, reasoning_tokens: 5 }, Mode::Sync);
+        tracker.record_text(PRO, tokens(1, 2), Mode::Sync);
+        tracker.record_images("art://folder/yandex-art/latest", 2);
+
+        let usage = tracker.usage();
+        let pro = &usage[PRO];
+        assert_eq!((pro.requests, pro.input_text_tokens, pro.completion_tokens, pro.reasoning_tokens), (2, 11, 22, 5));
+        assert_eq!(usage["art://folder/yandex-art/latest"].image_generations, 2);
+        assert!((tracker.spent() - 6.033).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_budgets() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let tracker = UsageTracker::new(PriceTable::new(0.0, 1.0))
+            .with_budget(Budget { soft: Some(2.0), hard: Some(3.0) })
+            .on_soft_limit(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+
+        tracker.record_images("art://f/yandex-art/latest", 1);
+        assert!(!tracker.is_soft_limit_exceeded());
+        tracker.record_images("art://f/yandex-art/latest", 1);
+        tracker.record_images("art://f/yandex-art/latest", 0);
+        assert!(tracker.is_soft_limit_exceeded());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(tracker.check().is_ok());
+
+        tracker.record_images("art://f/yandex-art/latest", 1);
+        assert_eq!(tracker.check(), Err(BudgetExceeded { spent: 3.0, limit: 3.0 }));
+
+        tracker.reset();
+        assert!(tracker.check().is_ok());
+    }
+
+    #[test]
+    fn test_reserve_and_release_images() {
+        const ART: &str = "art://f/yandex-art/latest";
+        let tracker = UsageTracker::new(PriceTable::new(0.0, 1.0)).with_budget(Budget { soft: None, hard: Some(3.0) });
+
+        tracker.reserve_images(ART, 2).unwrap();
+        assert_eq!(tracker.reserve_images(ART, 1), Err(BudgetExceeded { spent: 2.0, limit: 3.0 }));
+        assert_eq!(tracker.usage()[ART].image_generations, 2);
+
+        tracker.release_images(ART, 1);
+        tracker.reserve_images(ART, 1).unwrap();
+        assert_eq!(tracker.usage()[ART].image_generations, 2);
+        assert!((tracker.spent() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_check_and_reserve_share_the_boundary() {
+        const ART: &str = "art://f/yandex-art/latest";
+        let tracker = UsageTracker::new(PriceTable::new(0.0, 1.0)).with_budget(Budget { soft: None, hard: Some(2.0) });
+
+        tracker.reserve_images(ART, 1).unwrap();
+        assert!(tracker.check().is_ok());
+        // Reaching the limit is rejected by both, so no reservation leaves check failing
+        assert_eq!(tracker.reserve_images(ART, 1), Err(BudgetExceeded { spent: 1.0, limit: 2.0 }));
+        assert!(tracker.check().is_ok());
+        tracker.record_images(ART, 1);
+        assert_eq!(tracker.check(), Err(BudgetExceeded { spent: 2.0, limit: 2.0 }));
+        assert_eq!(tracker.reserve_images(ART, 0), Err(BudgetExceeded { spent: 2.0, limit: 2.0 }));
+    }
+
+    #[test]
+    fn test_requests_count_api_calls() {
+        const ART: &str = "art://f/yandex-art/latest";
+        let tracker = UsageTracker::new(PriceTable::new(0.0, 1.0));
+
+        tracker.record_images(ART, 3);
+        assert_eq!((tracker.usage()[ART].requests, tracker.usage()[ART].image_generations), (1, 3));
+
+        tracker.reserve_images(ART, 3).unwrap();
+        tracker.release_images(ART, 1);
+        assert_eq!((tracker.usage()[ART].requests, tracker.usage()[ART].image_generations), (3, 5));
+    }
+
+    #[test]
+    fn test_price_table_from_toml() {
+        let prices: PriceTable = toml::from_str(
+            "price_per_1k_units = 0.2\nimage_price = 2.0\nasync_discount = 0.5\n[multipliers]\nyandexgpt = 6.0\n",
+        )
+        .unwrap();
+        assert_eq!(prices, PriceTable::new(0.2, 2.0).with_async_discount(0.5).with_multiplier("yandexgpt", 6.0));
+    }
+}
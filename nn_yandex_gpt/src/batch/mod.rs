@@ -0,0 +1,450 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::models::model_uri::ModelUri;
+use crate::models::request::Request;
+use crate::models::response::Result as YandexResult;
+use crate::TextGenerator;
+use nn_yandex_core::usage::Mode;
+
+pub use nn_yandex_core::retry::RetryPolicy;
+
+/// A single line of the batch input file
+///
+/// The request fields are inlined, so a line looks like a regular completion request
+/// with an optional `id` and `model`:
+/// `{"id": "row-1", "model": "yandexgpt/rc", "messages": [{"role": "user", "text": "Hi"}]}`.
+/// Lines without `id` are identified by their 1-based line number, lines without `model`
+/// use the runner's model.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchItem {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<ModelUri>,
+
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+/// A single line of the batch output file
+///
+/// Exactly one of `result` and `error` is set
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchOutcome {
+    pub id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<YandexResult>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Number of times the request was sent
+    pub attempts: u32,
+}
+
+/// Counters reported after a batch run
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    /// Number of items in the input file
+    pub total: usize,
+    /// Items skipped because the output file already holds a result for them
+    pub skipped: usize,
+    /// Items completed successfully during this run
+    pub succeeded: usize,
+    /// Items that failed during this run
+    pub failed: usize,
+}
+
+/// Errors returned by the BatchRunner
+#[derive(Debug)]
+pub enum BatchError {
+    /// Failed to read the input or write the output file
+    Io(std::io::Error),
+    /// A line of the input or output file is not valid JSON
+    Parse { line: usize, source: serde_json::Error },
+    /// The same id appears more than once in the input file
+    DuplicateId(String),
+    /// Failed to serialize an outcome
+    Serialize(serde_json::Error),
+}
+
+impl std::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::Io(e) => write!(f, "IO error: {}", e),
+            BatchError::Parse { line, source } => write!(f, "Invalid JSON on line {}: {}", line, source),
+            BatchError::DuplicateId(id) => write!(f, "Duplicate id in input: {}", id),
+            BatchError::Serialize(e) => write!(f, "Failed to serialize outcome: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl From<std::io::Error> for BatchError {
+    fn from(e: std::io::Error) -> Self {
+        BatchError::Io(e)
+    }
+}
+
+/// Spaces out request starts so that no more than one starts per `interval`
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(interval: Duration) -> Self {
+        RateLimiter { interval, next: Mutex::new(Instant::now()) }
+    }
+
+    async fn acquire(&self) {
+        let slot = {
+            let mut next = self.next.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// Runs completion requests from a JSON Lines file and writes outcomes to another one
+///
+/// Outcomes are appended to the output file as they complete, in completion order.
+/// When the output file already exists, items that have a successful result in it are
+/// skipped, so an interrupted run can be resumed by starting it again with the same files.
+/// Failed items are retried on resume and their new outcome is appended after the old one.
+pub struct BatchRunner {
+    generator: TextGenerator,
+    model: ModelUri,
+    concurrency: usize,
+    rate_limit: Option<Duration>,
+    retry_policy: RetryPolicy,
+}
+
+impl BatchRunner {
+    /// Creates a runner that sends items without their own `model` to the given model
+    ///
+    /// The runner takes over the generator's retry policy and sends through a copy of the
+    /// generator that does not retry, so every attempt passes the rate limit. Completions
+    /// are recorded in the generator's usage tracker with `Mode::Async` pricing.
+    pub fn new(generator: &TextGenerator, model: impl Into<ModelUri>) -> Self {
+        let mut batch_generator = generator.clone().with_retry_policy(RetryPolicy::none());
+        batch_generator.inner_mut().usage_mode = Mode::Async;
+        BatchRunner {
+            generator: batch_generator,
+            model: model.into(),
+            concurrency: 4,
+            rate_limit: None,
+            retry_policy: generator.inner.retry_policy.clone(),
+        }
+    }
+
+    /// Maximum number of requests in flight (at least 1, default 4)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Starts at most `requests` requests (retries included) per `per`
+    pub fn with_rate_limit(mut self, requests: u32, per: Duration) -> Self {
+        self.rate_limit = Some(per / requests.max(1));
+        self
+    }
+
+    /// Retries failed items (the generator's policy by default)
+    ///
+    /// Replaces the generator's policy for the batch; each item is sent at most
+    /// `max_retries + 1` times
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Executes all pending items of `input` and appends their outcomes to `output`
+    pub async fn run(
+        &self,
+        input: impl AsRef<Path>,
+        output: impl AsRef<Path>,
+    ) -> Result<BatchSummary, BatchError> {
+        let input = tokio::fs::read_to_string(input).await?;
+        let items = parse_input(&input)?;
+
+        let mut existing = match tokio::fs::read(output.as_ref()).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(BatchError::Io(e)),
+        };
+        // Cut off the partial last line of an interrupted write so that outcomes appended
+        // now start on a line of their own
+        let complete = existing.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+        if complete < existing.len() {
+            OpenOptions::new().write(true).open(output.as_ref()).await?.set_len(complete as u64).await?;
+            existing.truncate(complete);
+        }
+        let existing = String::from_utf8(existing)
+            .map_err(|e| BatchError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+        let completed = completed_ids(&existing)?;
+
+        let mut file = OpenOptions::new().create(true).append(true).open(output).await?;
+
+        let mut summary = BatchSummary { total: items.len(), ..Default::default() };
+        let pending: Vec<(String, BatchItem)> = items
+            .into_iter()
+            .filter(|(id, _)| !completed.contains(id))
+            .collect();
+        summary.skipped = summary.total - pending.len();
+
+        let limiter = self.rate_limit.map(RateLimiter::new);
+        let mut outcomes = stream::iter(pending)
+            .map(|(id, item)| self.execute(id, item, limiter.as_ref()))
+            .buffer_unordered(self.concurrency);
+
+        while let Some(outcome) = outcomes.next().await {
+            let mut line = serde_json::to_string(&outcome).map_err(BatchError::Serialize)?;
+            line.push('\n');
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await?;
+
+            if outcome.result.is_some() {
+                summary.succeeded += 1;
+            } else {
+                summary.failed += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    async fn execute(&self, id: String, item: BatchItem, limiter: Option<&RateLimiter>) -> BatchOutcome {
+        let model = item.model.unwrap_or_else(|| self.model.clone());
+        let mut attempts = 0;
+        loop {
+            if let Some(limiter) = limiter {
+                limiter.acquire().await;
+            }
+            attempts += 1;
+
+            match self.generator.complete(model.clone(), item.request.clone()).await {
+                Ok(result) => {
+                    return BatchOutcome { id, result: Some(result), error: None, attempts };
+                }
+                Err(e) if e.is_retryable() && attempts <= self.retry_policy.max_retries() => {
+                    tokio::time::sleep(self.retry_policy.backoff(attempts - 1)).await;
+                }
+                Err(e) => {
+                    return BatchOutcome { id, result: None, error: Some(e.to_string()), attempts };
+                }
+            }
+        }
+    }
+}
+
+/// Parses the input file into items keyed by their resolved id, skipping blank lines
+fn parse_input(text: &str) -> Result<Vec<(String, BatchItem)>, BatchError> {
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let item: BatchItem = serde_json::from_str(line)
+            .map_err(|source| BatchError::Parse { line: index + 1, source })?;
+        let id = item.id.clone().unwrap_or_else(|| (index + 1).to_string());
+        if !seen.insert(id.clone()) {
+            return Err(BatchError::DuplicateId(id));
+        }
+        items.push((id, item));
+    }
+
+    Ok(items)
+}
+
+/// Collects ids that already have a successful result in the output file
+///
+/// An unterminated last line is the remains of an interrupted write and is ignored;
+/// `run` removes it from the file before appending
+fn completed_ids(text: &str) -> Result<HashSet<String>, BatchError> {
+    let mut ids = HashSet::new();
+    let terminated = text.ends_with('\n');
+    let lines: Vec<&str> = text.lines().collect();
+
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let outcome: BatchOutcome = match serde_json::from_str(line) {
+            Ok(outcome) => outcome,
+            Err(_) if !terminated && index + 1 == lines.len() => break,
+            Err(source) => return Err(BatchError::Parse { line: index + 1, source }),
+        };
+        if outcome.result.is_some() {
+            ids.insert(outcome.id);
+        }
+    }
+
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use nn_yandex_core::http::{HttpResponse, StatusCode};
+    use nn_yandex_core::transport::InMemoryTransport;
+
+    const RESULT: &str = r#"{"alternatives":[{"message":{"role":"assistant","text":"Hi"}}],"usage":{"inputTextTokens":"1","completionTokens":"1","totalTokens":"2"},"modelVersion":"1"}"#;
+
+    #[test]
+    fn test_parse_input_ids_and_blank_lines() {
+        let text = "{\"id\": \"a\", \"model\": \"ds://bt1\", \"messages\": [{\"role\": \"user\", \"text\": \"Hello\"}]}\n\n{\"messages\": []}\n";
+        let items = parse_input(text).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].0, "a");
+        assert_eq!(items[0].1.model, Some(ModelUri::fine_tuned("bt1").unwrap()));
+        assert_eq!(items[0].1.request.messages[0].text, "Hello");
+        assert_eq!(items[1].0, "3");
+        assert_eq!(items[1].1.model, None);
+    }
+
+    #[test]
+    fn test_parse_input_duplicate_id() {
+        let text = "{\"id\": \"a\"}\n{\"id\": \"a\"}\n";
+        assert!(matches!(parse_input(text), Err(BatchError::DuplicateId(id)) if id == "a"));
+    }
+
+    #[test]
+    fn test_parse_input_invalid_line() {
+        let text = "{\"id\": \"a\"}\nnot json\n";
+        assert!(matches!(parse_input(text), Err(BatchError::Parse { line: 2, .. })));
+    }
+
+    #[test]
+    fn test_completed_ids_skips_errors_and_truncated_tail() {
+        let text = format!(
+            "{{\"id\":\"a\",\"result\":{{\"result\":{RESULT}}},\"attempts\":1}}\n{{\"id\":\"b\",\"error\":\"boom\",\"attempts\":4}}\n{{\"id\":\"c\",\"res"
+        );
+        let ids = completed_ids(&text).unwrap();
+
+        assert_eq!(ids, HashSet::from(["a".to_string()]));
+    }
+
+    #[test]
+    fn test_completed_ids_rejects_corrupted_middle_line() {
+        let text = "garbage\n{\"id\":\"b\",\"error\":\"boom\",\"attempts\":1}\n";
+        assert!(matches!(completed_ids(text), Err(BatchError::Parse { line: 1, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_run_resumes_and_skips_completed() {
+        let dir = std::env::temp_dir().join(format!("nn_yandex_batch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("input.jsonl"), dir.join("output.jsonl"));
+        let line = |id: &str| format!("{{\"id\": \"{id}\", \"messages\": [{{\"role\": \"user\", \"text\": \"{id}\"}}]}}\n");
+        std::fs::write(&input, [line("a"), line("b"), line("c")].concat()).unwrap();
+        // "a" is done, "b" failed earlier and "c" was interrupted mid-write
+        std::fs::write(
+            &output,
+            format!("{{\"id\":\"a\",\"result\":{{\"result\":{RESULT}}},\"attempts\":1}}\n{{\"id\":\"b\",\"error\":\"boom\",\"attempts\":1}}\n{{\"id\":\"c\",\"res"),
+        )
+        .unwrap();
+
+        let transport = Arc::new(InMemoryTransport::new(|request| {
+            let body: serde_json::Value = serde_json::from_slice(request.body.as_deref().unwrap()).unwrap();
+            if body["messages"][0]["text"] == "c" {
+                return Ok(HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, r#"{"code":"UNAVAILABLE","message":"try later"}"#));
+            }
+            Ok(HttpResponse::new(StatusCode::OK, format!("{{\"result\":{RESULT}}}")))
+        }));
+        let generator = TextGenerator::new("key", "folder".to_string()).with_transport(transport.clone());
+        let runner = BatchRunner::new(&generator, crate::ModelType::GptLite);
+
+        let first = runner.run(&input, &output).await;
+        let after_first = std::fs::read_to_string(&output);
+        // Interrupt another write before resuming a second time
+        let mut file = std::fs::OpenOptions::new().append(true).open(&output).unwrap();
+        std::io::Write::write_all(&mut file, b"{\"id\":\"c\",\"err").unwrap();
+        let second = runner.run(&input, &output).await;
+        let after_second = std::fs::read_to_string(&output);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(first.unwrap(), BatchSummary { total: 3, skipped: 1, succeeded: 1, failed: 1 });
+        assert_eq!(second.unwrap(), BatchSummary { total: 3, skipped: 2, succeeded: 0, failed: 1 });
+        // Without retries every pending item is sent exactly once per run
+        assert_eq!(transport.requests().len(), 3);
+
+        let parse = |text: String| text.lines().map(|line| serde_json::from_str(line).unwrap()).collect::<Vec<BatchOutcome>>();
+        let outcomes = parse(after_first.unwrap());
+        assert_eq!(outcomes.len(), 4);
+        assert!(outcomes[2..].iter().any(|o| o.id == "b" && o.result.is_some()));
+        assert!(outcomes[2..].iter().any(|o| o.id == "c" && o.error.is_some() && o.attempts == 1));
+        let outcomes = parse(after_second.unwrap());
+        assert_eq!(outcomes.len(), 5);
+        assert!(outcomes[4].id == "c" && outcomes[4].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_runner_takes_over_generator_retries() {
+        let dir = std::env::temp_dir().join(format!("nn_yandex_batch_retries_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("input.jsonl"), dir.join("output.jsonl"));
+        std::fs::write(&input, "{\"id\": \"a\", \"messages\": [{\"role\": \"user\", \"text\": \"a\"}]}\n").unwrap();
+
+        let transport = Arc::new(InMemoryTransport::new(|_| {
+            Ok(HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, r#"{"code":"UNAVAILABLE","message":"try later"}"#))
+        }));
+        let generator = TextGenerator::new("key", "folder".to_string())
+            .with_transport(transport.clone())
+            .with_retry_policy(RetryPolicy::new().with_max_retries(3).with_initial_backoff(Duration::ZERO));
+        let runner = BatchRunner::new(&generator, crate::ModelType::GptLite).with_rate_limit(1000, Duration::from_secs(1));
+
+        let summary = runner.run(&input, &output).await;
+        let written = std::fs::read_to_string(&output);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(summary.unwrap().failed, 1);
+        // Four attempts in total, not (1 + 3) * (1 + 3)
+        assert_eq!(transport.requests().len(), 4);
+        let outcome: BatchOutcome = serde_json::from_str(written.unwrap().trim()).unwrap();
+        assert_eq!(outcome.attempts, 4);
+    }
+
+    #[tokio::test]
+    async fn test_run_records_async_pricing() {
+        use nn_yandex_core::usage::{PriceTable, UsageTracker};
+
+        let dir = std::env::temp_dir().join(format!("nn_yandex_batch_pricing_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (input, output) = (dir.join("input.jsonl"), dir.join("output.jsonl"));
+        std::fs::write(&input, "{\"id\": \"a\", \"messages\": [{\"role\": \"user\", \"text\": \"a\"}]}\n").unwrap();
+
+        let transport = Arc::new(InMemoryTransport::new(|_| Ok(HttpResponse::new(StatusCode::OK, format!("{{\"result\":{RESULT}}}")))));
+        let tracker = Arc::new(UsageTracker::new(PriceTable::new(1000.0, 0.0).with_async_discount(0.5)));
+        let generator = TextGenerator::new("key", "folder".to_string())
+            .with_transport(transport)
+            .with_usage_tracker(tracker.clone());
+        let runner = BatchRunner::new(&generator, crate::ModelType::GptLite);
+
+        let summary = runner.run(&input, &output).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(summary.unwrap().succeeded, 1);
+        // 2 tokens at 1.0 each, half price; the generator itself keeps sync pricing
+        assert!((tracker.spent() - 1.0).abs() < 1e-9);
+        assert_eq!(generator.inner.usage_mode, Mode::Sync);
+    }
+}
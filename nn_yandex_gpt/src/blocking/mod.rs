@@ -0,0 +1,138 @@
+//! Synchronous client for programs without an async runtime (`blocking` feature)
+
+use std::sync::Arc;
+
+use nn_yandex_core::auth::{Auth, CredentialCell};
+use nn_yandex_core::blocking::BlockingRuntime;
+use nn_yandex_core::config::Config;
+use nn_yandex_core::error::ConfigError;
+use nn_yandex_core::middleware::Middleware;
+use nn_yandex_core::retry::RetryPolicy;
+use nn_yandex_core::secret::Secret;
+use nn_yandex_core::transport::HttpTransport;
+use nn_yandex_core::usage::UsageTracker;
+
+use crate::GeneratorError;
+use crate::models::model_uri::ModelUri;
+use crate::models::request::Request;
+use crate::models::response::Result as YandexResult;
+
+/// Blocking counterpart of `nn_yandex_gpt::TextGenerator` using the same request and
+/// response types
+///
+/// Must not be used from inside an async runtime; use the async client there.
+#[derive(Clone)]
+pub struct TextGenerator {
+    inner: crate::TextGenerator,
+    runtime: BlockingRuntime,
+}
+
+impl TextGenerator {
+    /// Creates a new TextGenerator instance
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime cannot be created
+    pub fn new(api_key: impl Into<Secret>, bucket_id: String) -> Self {
+        crate::TextGenerator::new(api_key, bucket_id).into()
+    }
+
+    /// Creates a TextGenerator from a Config, see `nn_yandex_gpt::TextGenerator::from_config`
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        crate::TextGenerator::from_config(config).map(Self::from)
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.inner = self.inner.with_retry_policy(retry_policy);
+        self
+    }
+
+    pub fn with_usage_tracker(mut self, tracker: Arc<UsageTracker>) -> Self {
+        self.inner = self.inner.with_usage_tracker(tracker);
+        self
+    }
+
+    pub fn with_trace_content(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_trace_content(enabled);
+        self
+    }
+
+    pub fn with_validation(mut self, enabled: bool) -> Self {
+        self.inner = self.inner.with_validation(enabled);
+        self
+    }
+
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.inner = self.inner.with_transport(transport);
+        self
+    }
+
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.inner = self.inner.with_middleware(middleware);
+        self
+    }
+
+    pub fn with_credentials(mut self, credentials: CredentialCell) -> Self {
+        self.inner = self.inner.with_credentials(credentials);
+        self
+    }
+
+    pub fn credentials(&self) -> CredentialCell {
+        self.inner.credentials()
+    }
+
+    pub fn rotate_credentials(&self, auth: Auth) {
+        self.inner.rotate_credentials(auth);
+    }
+
+    /// Changes API key and bucket ID
+    #[deprecated(note = "detaches the client from its shared credential cell; use `rotate_credentials` to change the key, or build a new client for another folder")]
+    pub fn change_credentials(&mut self, api_key: impl Into<Secret>, bucket_id: String) {
+        #[allow(deprecated)]
+        self.inner.change_credentials(api_key, bucket_id);
+    }
+
+    /// Returns the model used by `complete_default`
+    pub fn default_model(&self) -> &ModelUri {
+        self.inner.default_model()
+    }
+
+    /// Returns the underlying async client
+    pub fn as_async(&self) -> &crate::TextGenerator {
+        &self.inner
+    }
+
+    /// Sends a text completion request to the default model and waits for the answer
+    pub fn complete_default(&self, request: Request) -> Result<YandexResult, GeneratorError> {
+        self.runtime.block_on(self.inner.complete_default(request))
+    }
+
+    /// Sends a text completion request and waits for the answer
+    pub fn complete(&self, model: impl Into<ModelUri>, request: Request) -> Result<YandexResult, GeneratorError> {
+        self.runtime.block_on(self.inner.complete(model, request))
+    }
+}
+
+impl From<crate::TextGenerator> for TextGenerator {
+    /// Wraps an async client, creating the internal runtime
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime cannot be created
+    fn from(inner: crate::TextGenerator) -> Self {
+        TextGenerator { inner, runtime: BlockingRuntime::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::request::RequestBuilder;
+
+    #[test]
+    fn test_validation_error_without_network() {
+        let generator = TextGenerator::new("key", "folder".to_string());
+        let result = generator.complete_default(RequestBuilder::new().build());
+        assert!(matches!(result, Err(GeneratorError::Validation(_))));
+    }
+}
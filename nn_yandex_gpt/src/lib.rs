@@ -1,58 +1,49 @@
 pub mod models;
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 
-use reqwest;
+use models::model_uri::{ModelUri, ModelUriError};
 use models::request::Request;
+use models::request::validation::ValidationError;
 use models::response::Result as YandexResult;
 use models::response::Error as YandexError;
 use std::future::Future;
+use std::sync::Arc;
+use nn_yandex_core::auth::{Auth, CredentialCell};
+use nn_yandex_core::config::Config;
+use nn_yandex_core::error::{BudgetExceeded, ConfigError, InvalidCredentials};
+use nn_yandex_core::http::{HttpRequest, StatusCode};
+use nn_yandex_core::middleware::{Middleware, MiddlewareError, MiddlewareStack};
+use nn_yandex_core::retry::RetryPolicy;
+use nn_yandex_core::secret::{Secret, redact};
+use nn_yandex_core::transport::{HttpTransport, ReqwestTransport, TransportError};
+use nn_yandex_core::telemetry::{CallSpan, Endpoint, ErrorCode};
+use nn_yandex_core::usage::{Mode, UsageTracker};
 
-const YANDEX_GPT_URL: &str = "https://llm.api.cloud.yandex.net/foundationModels/v1/completion";
-
-/// Supported model types
-pub enum ModelType {
-    GptLite,
-    GptPro,
-    Llama8B,
-    Llama70B,
-}
-
-impl ModelType {
-    /// Returns the model URI segment as string
-    pub fn as_str(&self) -> &str {
-        match self {
-            ModelType::GptLite => "yandexgpt-lite",
-            ModelType::GptPro => "yandexgpt",
-            ModelType::Llama8B => "llama-lite",
-            ModelType::Llama70B => "llama",
-        }
-    }
-}
-
-/// Supported versions
-pub enum Version {
-    Deprecated,
-    Latest,
-    RC,
-}
+pub use models::model_type::{ModelCapabilities, ModelType, Version};
 
-impl Version {
-    /// Returns the version as string
-    pub fn as_str(&self) -> &str {
-        match self {
-            Version::Deprecated => "deprecated",
-            Version::Latest => "latest",
-            Version::RC => "rc",
-        }
-    }
-}
+const YANDEX_GPT_URL: &str = "https://llm.api.cloud.yandex.net/foundationModels/v1/completion";
 
 /// Errors returned by the TextGenerator
 #[derive(Debug)]
 pub enum GeneratorError {
-    /// HTTP request error
-    Http(reqwest::Error),
+    /// The request could not be sent or the response could not be received
+    Http(TransportError),
     /// Error returned by Yandex API
     Api(YandexError),
+    /// Non-success HTTP status whose body could not be parsed as an API error
+    Status(StatusCode),
+    /// The response body could not be parsed
+    Decode(serde_json::Error),
+    /// A middleware rejected the request
+    Middleware(MiddlewareError),
+    /// The credentials cannot be sent; the request was not sent
+    Auth(InvalidCredentials),
+    /// The request failed client-side validation and was not sent
+    Validation(ValidationError),
+    /// The usage tracker's hard budget is spent; the request was not sent
+    Budget(BudgetExceeded),
     /// Unknown error
     Unknown(String),
 }
@@ -60,72 +51,471 @@ pub enum GeneratorError {
 impl std::fmt::Display for GeneratorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            GeneratorError::Http(e) => write!(f, "HTTP error: {}", e),
+            GeneratorError::Http(e) => write!(f, "HTTP error: {}", redact(&e.to_string())),
             GeneratorError::Api(e) => write!(f, "API error: {:?}", e),
-            GeneratorError::Unknown(msg) => write!(f, "Unknown error: {}", msg),
+            GeneratorError::Status(status) => write!(f, "request failed with status: {}", status),
+            GeneratorError::Decode(e) => write!(f, "Invalid response: {}", e),
+            GeneratorError::Middleware(e) => write!(f, "Rejected by middleware: {}", e),
+            GeneratorError::Auth(e) => write!(f, "{}", e),
+            GeneratorError::Validation(e) => write!(f, "{}", e),
+            GeneratorError::Budget(e) => write!(f, "{}", e),
+            GeneratorError::Unknown(msg) => write!(f, "Unknown error: {}", redact(msg)),
         }
     }
 }
 
 impl std::error::Error for GeneratorError {}
 
+impl GeneratorError {
+    /// Returns `true` if the failure is transient and the request may succeed when repeated
+    /// (timeouts, connection failures, rate limiting and server-side errors)
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            GeneratorError::Http(e) => e.is_timeout() || e.is_connect(),
+            GeneratorError::Api(e) => matches!(
+                e.code.as_str(),
+                "RESOURCE_EXHAUSTED" | "UNAVAILABLE" | "DEADLINE_EXCEEDED" | "INTERNAL"
+                    | "429" | "500" | "502" | "503" | "504"
+            ),
+            GeneratorError::Status(status) => is_retryable_status(*status),
+            GeneratorError::Decode(_)
+            | GeneratorError::Middleware(_)
+            | GeneratorError::Auth(_)
+            | GeneratorError::Validation(_)
+            | GeneratorError::Budget(_)
+            | GeneratorError::Unknown(_) => false,
+        }
+    }
+}
+
+impl ErrorCode for GeneratorError {
+    fn error_code(&self) -> String {
+        match self {
+            GeneratorError::Http(_) => "http".to_string(),
+            GeneratorError::Api(e) => e.code.clone(),
+            GeneratorError::Status(status) => status.as_str().to_string(),
+            GeneratorError::Decode(_) => "decode".to_string(),
+            GeneratorError::Middleware(_) => "middleware".to_string(),
+            GeneratorError::Auth(_) => "auth".to_string(),
+            GeneratorError::Validation(_) => "validation".to_string(),
+            GeneratorError::Budget(_) => "budget".to_string(),
+            GeneratorError::Unknown(_) => "unknown".to_string(),
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
 /// Main structure for text generation
+///
+/// Cloning is cheap: clones share the HTTP client, credentials, middleware and usage
+/// tracker. Builder methods called on a clone only affect that clone.
+#[derive(Clone)]
 pub struct TextGenerator {
-    api_key: String,
+    inner: Arc<Inner>,
+}
+
+#[derive(Clone)]
+struct Inner {
+    auth: CredentialCell,
     bucket_id: String,
-    client: reqwest::Client,
+    transport: Arc<dyn HttpTransport>,
+    completion_url: String,
+    retry_policy: RetryPolicy,
+    default_model: ModelUri,
+    usage_tracker: Option<Arc<UsageTracker>>,
+    /// How completions are billed in the usage tracker; `Async` for batch runs
+    usage_mode: Mode,
+    trace_content: bool,
+    validate: bool,
+    middleware: MiddlewareStack,
 }
 
 impl TextGenerator {
     /// Creates a new TextGenerator instance
-    pub fn new(api_key: String, bucket_id: String) -> Self {
-        Self {
-            api_key,
+    pub fn new(api_key: impl Into<Secret>, bucket_id: String) -> Self {
+        Self::from_inner(Inner {
+            auth: CredentialCell::new(Auth::api_key(api_key)),
             bucket_id,
-            client: reqwest::Client::new(),
-        }
+            transport: Arc::new(ReqwestTransport::default()),
+            completion_url: YANDEX_GPT_URL.to_string(),
+            retry_policy: RetryPolicy::none(),
+            default_model: ModelUri::preset(ModelType::GptPro, Version::Latest),
+            usage_tracker: None,
+            usage_mode: Mode::Sync,
+            trace_content: false,
+            validate: true,
+            middleware: MiddlewareStack::new(),
+        })
+    }
+
+    /// Creates a TextGenerator from a Config
+    ///
+    /// Uses the configured auth method, completion endpoint, timeouts and retry policy.
+    /// The configured model becomes the default for `complete_default` (`yandexgpt/latest`
+    /// when not set). `model` is parsed as a ModelUri; `version` applies to a bare model name.
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let invalid = |field, message: String| ConfigError::Invalid { field, message };
+        let default_model = match (config.model.as_deref(), config.version.as_deref()) {
+            (None, None) => ModelUri::preset(ModelType::GptPro, Version::Latest),
+            (None, Some(version)) => ModelUri::new(ModelType::GptPro.as_str(), version)
+                .map_err(|e| invalid("version", e.to_string()))?,
+            (Some(model), None) => model.parse().map_err(|e: ModelUriError| invalid("model", e.to_string()))?,
+            (Some(model), Some(_)) if model.contains('/') => {
+                return Err(invalid("version", format!("model {model} already includes a version")));
+            }
+            (Some(model), Some(version)) => ModelUri::new(model, version)
+                .map_err(|e| invalid("model", e.to_string()))?,
+        };
+
+        Ok(Self::from_inner(Inner {
+            auth: CredentialCell::new(config.auth.clone()),
+            bucket_id: config.folder_id.clone(),
+            transport: Arc::new(ReqwestTransport::new(config.http_client()?)),
+            completion_url: config
+                .endpoints
+                .completion
+                .clone()
+                .unwrap_or_else(|| YANDEX_GPT_URL.to_string()),
+            retry_policy: config.retry_policy.clone(),
+            default_model,
+            usage_tracker: None,
+            usage_mode: Mode::Sync,
+            trace_content: false,
+            validate: true,
+            middleware: MiddlewareStack::new(),
+        }))
+    }
+
+    fn from_inner(inner: Inner) -> Self {
+        TextGenerator { inner: Arc::new(inner) }
+    }
+
+    /// Returns the settings for modification, copying them if they are shared with a clone
+    fn inner_mut(&mut self) -> &mut Inner {
+        Arc::make_mut(&mut self.inner)
+    }
+
+    /// Uses a shared credential cell, e.g. one also given to an `Art` client
+    pub fn with_credentials(mut self, credentials: CredentialCell) -> Self {
+        self.inner_mut().auth = credentials;
+        self
+    }
+
+    /// Returns a handle to this client's credentials that a secrets watcher can
+    /// `rotate` while requests are running
+    pub fn credentials(&self) -> CredentialCell {
+        self.inner.auth.clone()
+    }
+
+    /// Replaces the credentials without exclusive access; in-flight requests are not
+    /// interrupted and clients sharing the credential cell are rotated as well
+    pub fn rotate_credentials(&self, auth: Auth) {
+        self.inner.auth.rotate(auth);
     }
 
     /// Changes API key and bucket ID
-    pub fn change_credentials(&mut self, api_key: String, bucket_id: String) {
-        self.api_key = api_key;
-        self.bucket_id = bucket_id;
+    ///
+    /// This client gets a new credential cell: handles from `credentials()` and clients
+    /// sharing the old cell keep the old key and are no longer rotated with this client.
+    #[deprecated(note = "detaches the client from its shared credential cell; use `rotate_credentials` to change the key, or build a new client for another folder")]
+    pub fn change_credentials(&mut self, api_key: impl Into<Secret>, bucket_id: String) {
+        self.inner_mut().auth = CredentialCell::new(Auth::api_key(api_key));
+        self.inner_mut().bucket_id = bucket_id;
+    }
+
+    /// Sets the retry policy for transient failures (no retries by default)
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.inner_mut().retry_policy = retry_policy;
+        self
+    }
+
+    /// Records token usage of every successful completion in `tracker` and rejects
+    /// requests with `GeneratorError::Budget` once its hard budget is spent
+    pub fn with_usage_tracker(mut self, tracker: Arc<UsageTracker>) -> Self {
+        self.inner_mut().usage_tracker = Some(tracker);
+        self
+    }
+
+    /// Logs prompts and responses as TRACE events of the `complete` span
+    /// (requires the `tracing` feature; off by default)
+    pub fn with_trace_content(mut self, enabled: bool) -> Self {
+        self.inner_mut().trace_content = enabled;
+        self
+    }
+
+    /// Validates requests before sending them (on by default)
+    ///
+    /// Turn it off when the model capability table rejects requests the API accepts;
+    /// the API then reports invalid requests itself.
+    pub fn with_validation(mut self, enabled: bool) -> Self {
+        self.inner_mut().validate = enabled;
+        self
+    }
+
+    /// Sends requests through `transport` instead of the default reqwest client
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.inner_mut().transport = Arc::new(transport);
+        self
+    }
+
+    /// Adds a middleware run around every HTTP call, retries included
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.inner_mut().middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Returns the model used by `complete_default`
+    pub fn default_model(&self) -> &ModelUri {
+        &self.inner.default_model
+    }
+
+    /// Sends a text completion request to the default model
+    pub fn complete_default(
+        &self,
+        request: Request,
+    ) -> impl Future<Output = Result<YandexResult, GeneratorError>> + Send + 'static {
+        self.complete(self.inner.default_model.clone(), request)
     }
 
     /// Sends a text completion request
-    /// Returns a Future instead of being async
+    /// Returns a `Send + 'static` Future instead of being async, so it can be spawned
+    ///
+    /// `model` is a ModelUri or anything convertible into one, e.g. `(ModelType::GptPro, Version::RC)`.
+    /// Unless turned off with `with_validation(false)`, the request is validated against
+    /// the model's capabilities first and is not sent if validation fails.
     pub fn complete(
         &self,
-        model: ModelType,
-        version: Version,
+        model: impl Into<ModelUri>,
         mut request: Request,
-    ) -> impl Future<Output = Result<YandexResult, GeneratorError>> + '_ {
-        let client = &self.client;
-        let api_key = &self.api_key;
-        let bucket_id = &self.bucket_id;
-
-        async move {
-            request.model_uri = format!("gpt://{}/{}/{}", bucket_id, model.as_str(), version.as_str());
-
-            let resp = client
-                .post(YANDEX_GPT_URL)
-                .header("Authorization", format!("Api-Key {}", api_key))
-                .json(&request)
-                .send()
-                .await
-                .map_err(GeneratorError::Http)?;
-
-            let status = resp.status().clone();
-
-            if !resp.status().is_success() {
-                return match resp.json::<YandexError>().await {
-                    Ok(err) => Err(GeneratorError::Api(err)),
-                    Err(_) => Err(GeneratorError::Unknown(format!("request failed with status: {status}"))),
-                }
+    ) -> impl Future<Output = Result<YandexResult, GeneratorError>> + Send + 'static {
+        let model = model.into();
+        let validation = match self.inner.validate {
+            true => request.validate(model.capabilities().as_ref()),
+            false => Ok(()),
+        };
+        request.model_uri = model.resolve(&self.inner.bucket_id);
+
+        let inner = self.inner.clone();
+        let span = CallSpan::new(Endpoint::Complete, inner.trace_content).with_model_uri(&request.model_uri);
+
+        span.run(move |span| async move {
+            validation.map_err(GeneratorError::Validation)?;
+            if let Some(tracker) = &inner.usage_tracker {
+                tracker.check().map_err(GeneratorError::Budget)?;
             }
 
-            let result = resp.json::<YandexResult>().await.map_err(GeneratorError::Http)?;
+            span.trace_content("prompt", || serde_json::to_string(&request.messages).unwrap_or_default());
+
+            let mut retry = 0;
+            let result = loop {
+                match inner.send(&request, &span).await {
+                    Err(e) if e.is_retryable() && retry < inner.retry_policy.max_retries() => {
+                        tokio::time::sleep(inner.retry_policy.backoff(retry)).await;
+                        retry += 1;
+                    }
+                    result => {
+                        span.record_retries(retry);
+                        break result?;
+                    }
+                }
+            };
+
+            let tokens = (&result.result.usage).into();
+            span.record_tokens(tokens);
+            span.trace_content("response", || result.text().unwrap_or_default().to_string());
+            if let Some(tracker) = &inner.usage_tracker {
+                tracker.record_text(&request.model_uri, tokens, inner.usage_mode);
+            }
             Ok(result)
+        })
+    }
+}
+
+impl Inner {
+    async fn send(&self, request: &Request, span: &CallSpan) -> Result<YandexResult, GeneratorError> {
+        let mut http_request = HttpRequest::post(&self.completion_url)
+            .with_auth(&self.auth.load())
+            .map_err(GeneratorError::Auth)?
+            .with_json(request)
+            .map_err(|e| GeneratorError::Unknown(format!("Failed to serialize request: {e}")))?;
+        self.middleware.before_request(&mut http_request).map_err(GeneratorError::Middleware)?;
+
+        let result = self.exchange(&http_request, span).await;
+        if let Err(e) = &result {
+            self.middleware.on_error(&http_request, e);
+        }
+        result
+    }
+
+    async fn exchange(&self, request: &HttpRequest, span: &CallSpan) -> Result<YandexResult, GeneratorError> {
+        let mut resp = self.transport.send(request).await.map_err(GeneratorError::Http)?;
+        span.record_status(resp.status.as_u16());
+        self.middleware.after_response(request, &mut resp);
+
+        if !resp.status.is_success() {
+            return match resp.json::<YandexError>() {
+                Ok(err) => Err(GeneratorError::Api(err)),
+                Err(_) => Err(GeneratorError::Status(resp.status)),
+            }
         }
+
+        resp.json::<YandexResult>().map_err(GeneratorError::Decode)
+    }
+}
+
+// Compile-time guarantee that public types can be shared between threads and tasks
+const _: () = {
+    use models::message::{FunctionCall, FunctionResult, Message, MessageBuilder, Role, ToolCallList, ToolCallWrapper, ToolResultList, ToolResultWrapper};
+    use models::request::validation::Violation;
+    use models::request::{CompletionOptions, Function, FunctionWrapper, JsonSchema, ReasoningMode, ReasoningOptions, RequestBuilder, ToolChoice};
+    use models::response::{Alternative, AlternativeStatus, CompletionTokensDetails, ResultWrapper, Usage};
+
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<TextGenerator>();
+    #[cfg(feature = "blocking")]
+    assert_send_sync::<blocking::TextGenerator>();
+    assert_send_sync::<GeneratorError>();
+    assert_send_sync::<batch::BatchRunner>();
+    assert_send_sync::<batch::BatchItem>();
+    assert_send_sync::<batch::BatchOutcome>();
+    assert_send_sync::<batch::BatchSummary>();
+    assert_send_sync::<batch::BatchError>();
+    assert_send_sync::<ModelUri>();
+    assert_send_sync::<ModelUriError>();
+    assert_send_sync::<ModelType>();
+    assert_send_sync::<Version>();
+    assert_send_sync::<ModelCapabilities>();
+    assert_send_sync::<Request>();
+    assert_send_sync::<RequestBuilder>();
+    assert_send_sync::<CompletionOptions>();
+    assert_send_sync::<ReasoningMode>();
+    assert_send_sync::<ReasoningOptions>();
+    assert_send_sync::<FunctionWrapper>();
+    assert_send_sync::<Function>();
+    assert_send_sync::<JsonSchema>();
+    assert_send_sync::<ToolChoice>();
+    assert_send_sync::<ValidationError>();
+    assert_send_sync::<Violation>();
+    assert_send_sync::<Message>();
+    assert_send_sync::<MessageBuilder>();
+    assert_send_sync::<Role>();
+    assert_send_sync::<ToolCallList>();
+    assert_send_sync::<ToolCallWrapper>();
+    assert_send_sync::<FunctionCall>();
+    assert_send_sync::<ToolResultList>();
+    assert_send_sync::<ToolResultWrapper>();
+    assert_send_sync::<FunctionResult>();
+    assert_send_sync::<YandexResult>();
+    assert_send_sync::<ResultWrapper>();
+    assert_send_sync::<YandexError>();
+    assert_send_sync::<Alternative>();
+    assert_send_sync::<AlternativeStatus>();
+    assert_send_sync::<Usage>();
+    assert_send_sync::<CompletionTokensDetails>();
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::message::{MessageBuilder, Role};
+    use models::request::RequestBuilder;
+    use nn_yandex_core::http::{HttpResponse, StatusCode};
+    use nn_yandex_core::transport::InMemoryTransport;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    const RESULT: &str = r#"{"result":{"alternatives":[{"message":{"role":"assistant","text":"Hi"},"status":"ALTERNATIVE_STATUS_FINAL"}],"usage":{"inputTextTokens":"3","completionTokens":"1","totalTokens":"4"},"modelVersion":"1"}}"#;
+    const UNAVAILABLE: &str = r#"{"code":"UNAVAILABLE","message":"try later"}"#;
+
+    fn request() -> Request {
+        RequestBuilder::new()
+            .message(MessageBuilder::new().with_role(Role::User).with_text("Hello").build())
+            .build()
+    }
+
+    fn generator(transport: Arc<InMemoryTransport>) -> TextGenerator {
+        TextGenerator::new("key", "folder".to_string()).with_transport(transport)
+    }
+
+    #[tokio::test]
+    async fn test_complete_sends_resolved_request() {
+        let transport = Arc::new(InMemoryTransport::new(|_| Ok(HttpResponse::new(StatusCode::OK, RESULT))));
+        let result = generator(transport.clone()).complete_default(request()).await.unwrap();
+
+        assert_eq!(result.text(), Some("Hi"));
+        let sent = &transport.requests()[0];
+        assert_eq!(sent.url, YANDEX_GPT_URL);
+        assert_eq!(sent.headers[reqwest::header::AUTHORIZATION], "Api-Key key");
+        let body: serde_json::Value = serde_json::from_slice(sent.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["modelUri"], "gpt://folder/yandexgpt/latest");
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_api_errors() {
+        let transport = Arc::new(InMemoryTransport::from_responses([
+            Ok(HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, UNAVAILABLE)),
+            Ok(HttpResponse::new(StatusCode::OK, RESULT)),
+        ]));
+        let generator = generator(transport.clone())
+            .with_retry_policy(RetryPolicy::new().with_initial_backoff(Duration::ZERO));
+
+        assert!(generator.complete_default(request()).await.is_ok());
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_hooks() {
+        #[derive(Clone, Default)]
+        struct Log(Arc<Mutex<Vec<String>>>);
+
+        impl Middleware for Log {
+            fn after_response(&self, _: &HttpRequest, response: &mut HttpResponse) {
+                self.0.lock().unwrap().push(format!("response {}", response.status.as_u16()));
+            }
+
+            fn on_error(&self, _: &HttpRequest, error: &(dyn std::error::Error + 'static)) {
+                self.0.lock().unwrap().push(format!("error {error}"));
+            }
+        }
+
+        let log = Log::default();
+        let transport = Arc::new(InMemoryTransport::new(|_| {
+            Ok(HttpResponse::new(StatusCode::SERVICE_UNAVAILABLE, UNAVAILABLE))
+        }));
+        let result = generator(transport).with_middleware(log.clone()).complete_default(request()).await;
+
+        assert!(matches!(result, Err(GeneratorError::Api(ref e)) if e.code == "UNAVAILABLE"));
+        let log = log.0.lock().unwrap();
+        assert_eq!(log[0], "response 503");
+        assert!(log[1].starts_with("error API error"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_credentials_are_not_sent() {
+        let transport = Arc::new(InMemoryTransport::new(|_| Ok(HttpResponse::new(StatusCode::OK, RESULT))));
+        let generator = TextGenerator::new("AQVN\nkey", "folder".to_string()).with_transport(transport.clone());
+
+        let result = generator.complete(ModelType::GptLite, request()).await;
+        assert!(matches!(result, Err(GeneratorError::Auth(_))));
+        assert!(transport.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validation_can_be_disabled() {
+        let transport = Arc::new(InMemoryTransport::new(|_| Ok(HttpResponse::new(StatusCode::OK, RESULT))));
+        let mut request = request();
+        request.completion_options = Some(models::request::CompletionOptions::new().with_max_tokens(100_000));
+
+        let result = generator(transport.clone()).complete(ModelType::Llama8B, request.clone()).await;
+        assert!(matches!(result, Err(GeneratorError::Validation(_))));
+        assert!(transport.requests().is_empty());
+
+        let result = generator(transport.clone()).with_validation(false).complete(ModelType::Llama8B, request).await;
+        assert!(result.is_ok());
+        assert_eq!(transport.requests().len(), 1);
     }
 }
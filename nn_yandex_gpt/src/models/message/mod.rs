@@ -22,6 +22,12 @@ pub struct MessageBuilder{
     tool_result_list: Option<ToolResultList>
 }
 
+impl Default for MessageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MessageBuilder {
     pub fn new() -> Self {
         MessageBuilder { role: None, text: None, tool_call_list: None, tool_result_list: None }
@@ -0,0 +1,223 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::models::model_uri::{ModelUriError, validate_segment};
+
+/// Supported model types
+///
+/// Parses from and displays as the model URI segment (`yandexgpt-lite`, `llama`, ...);
+/// unknown names parse into `Custom`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ModelType {
+    GptLite,
+    GptPro,
+    Llama8B,
+    Llama70B,
+    /// Any other model name, sent as-is
+    Custom(String),
+}
+
+/// What a model supports, used to validate requests before sending them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    /// Maximum number of tokens in the prompt and completion combined
+    pub context_length: u32,
+    /// Function calling via `tools`
+    pub supports_tools: bool,
+    /// `reasoningOptions` in completion options
+    pub supports_reasoning: bool,
+    /// `jsonObject` / `jsonSchema` response formats
+    pub supports_structured_output: bool,
+}
+
+impl ModelType {
+    /// Returns the model URI segment as string
+    pub fn as_str(&self) -> &str {
+        match self {
+            ModelType::GptLite => "yandexgpt-lite",
+            ModelType::GptPro => "yandexgpt",
+            ModelType::Llama8B => "llama-lite",
+            ModelType::Llama70B => "llama",
+            ModelType::Custom(name) => name,
+        }
+    }
+
+    /// Returns the capabilities of a known model, `None` for custom models
+    ///
+    /// The table is a snapshot of the Yandex Cloud documentation and may lag behind the
+    /// API; `TextGenerator::with_validation(false)` skips the checks based on it.
+    pub fn capabilities(&self) -> Option<ModelCapabilities> {
+        // Sources, all under https://yandex.cloud/en/docs/foundation-models/:
+        // * context_length: "Models available in synchronous mode" table in
+        //   concepts/yandexgpt/models (32K tokens for YandexGPT, 8K for Llama)
+        // * supports_tools: "Function calling" in concepts/yandexgpt, which lists only
+        //   the YandexGPT models
+        // * supports_reasoning: "Reasoning mode" in concepts/yandexgpt, available for
+        //   YandexGPT Pro only
+        // * supports_structured_output: "Structured output" in concepts/yandexgpt, which
+        //   covers every model of the synchronous completion API
+        let capabilities = match self {
+            ModelType::GptLite => ModelCapabilities {
+                context_length: 32_768,
+                supports_tools: true,
+                supports_reasoning: false,
+                supports_structured_output: true,
+            },
+            ModelType::GptPro => ModelCapabilities {
+                context_length: 32_768,
+                supports_tools: true,
+                supports_reasoning: true,
+                supports_structured_output: true,
+            },
+            ModelType::Llama8B | ModelType::Llama70B => ModelCapabilities {
+                context_length: 8_192,
+                supports_tools: false,
+                supports_reasoning: false,
+                supports_structured_output: true,
+            },
+            ModelType::Custom(_) => return None,
+        };
+        Some(capabilities)
+    }
+}
+
+impl FromStr for ModelType {
+    type Err = ModelUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "yandexgpt-lite" => ModelType::GptLite,
+            "yandexgpt" => ModelType::GptPro,
+            "llama-lite" => ModelType::Llama8B,
+            "llama" => ModelType::Llama70B,
+            _ => {
+                validate_segment(s)?;
+                ModelType::Custom(s.to_string())
+            }
+        })
+    }
+}
+
+impl fmt::Display for ModelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Supported versions
+///
+/// Parses from and displays as the version URI segment (`latest`, `rc`, ...);
+/// unknown versions parse into `Custom`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Version {
+    Deprecated,
+    Latest,
+    RC,
+    /// Any other version string, sent as-is
+    Custom(String),
+}
+
+impl Version {
+    /// Returns the version as string
+    pub fn as_str(&self) -> &str {
+        match self {
+            Version::Deprecated => "deprecated",
+            Version::Latest => "latest",
+            Version::RC => "rc",
+            Version::Custom(version) => version,
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = ModelUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "deprecated" => Version::Deprecated,
+            "latest" => Version::Latest,
+            "rc" => Version::RC,
+            _ => {
+                validate_segment(s)?;
+                Version::Custom(s.to_string())
+            }
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+macro_rules! impl_serde_as_str {
+    ($ty:ty) => {
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_serde_as_str!(ModelType);
+impl_serde_as_str!(Version);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_type_round_trip() {
+        for model in [ModelType::GptLite, ModelType::GptPro, ModelType::Llama8B, ModelType::Llama70B] {
+            assert_eq!(model.to_string().parse::<ModelType>().unwrap(), model);
+        }
+        assert_eq!(
+            "qwen3-235b-a22b-fp8".parse::<ModelType>().unwrap(),
+            ModelType::Custom("qwen3-235b-a22b-fp8".to_string())
+        );
+        assert!("".parse::<ModelType>().is_err());
+        assert!("yandex gpt".parse::<ModelType>().is_err());
+    }
+
+    #[test]
+    fn test_version_round_trip() {
+        for version in [Version::Deprecated, Version::Latest, Version::RC] {
+            assert_eq!(version.to_string().parse::<Version>().unwrap(), version);
+        }
+        assert_eq!("2024-01".parse::<Version>().unwrap(), Version::Custom("2024-01".to_string()));
+    }
+
+    #[test]
+    fn test_serde() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Defaults {
+            model: ModelType,
+            version: Version,
+        }
+
+        let defaults: Defaults = serde_json::from_str(r#"{"model": "yandexgpt-lite", "version": "rc"}"#).unwrap();
+        assert_eq!(defaults, Defaults { model: ModelType::GptLite, version: Version::RC });
+        assert_eq!(
+            serde_json::to_string(&defaults).unwrap(),
+            r#"{"model":"yandexgpt-lite","version":"rc"}"#
+        );
+    }
+
+    #[test]
+    fn test_capabilities() {
+        assert!(ModelType::GptPro.capabilities().unwrap().supports_reasoning);
+        assert!(!ModelType::Llama70B.capabilities().unwrap().supports_tools);
+        assert_eq!(ModelType::Custom("my-model".to_string()).capabilities(), None);
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{ModelCapabilities, ModelType, Version};
+
+const SCHEMES: [&str; 2] = ["gpt", "ds"];
+
+/// Model addressed by a completion request
+///
+/// Either a foundation model in the client's folder (`yandexgpt-lite/rc`, resolved to
+/// `gpt://<folder>/yandexgpt-lite/rc` when the request is sent) or a fully qualified URI
+/// that is sent as-is (`gpt://<folder>/yandexgpt/latest`, `ds://<fine-tuned model id>`).
+///
+/// Parsing accepts `name`, `name/version` and `scheme://...`; `Display` produces the same forms
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ModelUri(Repr);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Repr {
+    Foundation { name: String, version: String },
+    Raw(String),
+}
+
+/// Errors returned when building or parsing a ModelUri
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelUriError {
+    /// The URI or one of its segments is empty
+    Empty,
+    /// The scheme is not `gpt` or `ds`
+    UnsupportedScheme(String),
+    /// A segment contains whitespace or `/`, or a `gpt://` URI has the wrong number of segments
+    Invalid(String),
+}
+
+impl fmt::Display for ModelUriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModelUriError::Empty => write!(f, "Model URI is empty"),
+            ModelUriError::UnsupportedScheme(scheme) => write!(f, "Unsupported model URI scheme: {}", scheme),
+            ModelUriError::Invalid(uri) => write!(f, "Invalid model URI: {}", uri),
+        }
+    }
+}
+
+impl std::error::Error for ModelUriError {}
+
+impl ModelUri {
+    /// Foundation model with an arbitrary name and version, e.g. `("yandexgpt-32k", "rc")`
+    pub fn new(name: &str, version: &str) -> Result<Self, ModelUriError> {
+        validate_segment(name)?;
+        validate_segment(version)?;
+        Ok(ModelUri(Repr::Foundation { name: name.to_string(), version: version.to_string() }))
+    }
+
+    /// Foundation model from the known presets
+    pub fn preset(model: ModelType, version: Version) -> Self {
+        ModelUri(Repr::Foundation { name: model.as_str().to_string(), version: version.as_str().to_string() })
+    }
+
+    /// Fully qualified URI such as `gpt://<folder>/<name>/<version>` or `ds://<id>`
+    pub fn raw(uri: &str) -> Result<Self, ModelUriError> {
+        let (scheme, path) = uri.split_once("://").ok_or_else(|| ModelUriError::Invalid(uri.to_string()))?;
+        if !SCHEMES.contains(&scheme) {
+            return Err(ModelUriError::UnsupportedScheme(scheme.to_string()));
+        }
+
+        let segments: Vec<&str> = path.split('/').collect();
+        for segment in &segments {
+            validate_segment(segment)?;
+        }
+        // gpt://<folder>/<name> or gpt://<folder>/<name>/<version>
+        if scheme == "gpt" && !(2..=3).contains(&segments.len()) {
+            return Err(ModelUriError::Invalid(uri.to_string()));
+        }
+
+        Ok(ModelUri(Repr::Raw(uri.to_string())))
+    }
+
+    /// Fine-tuned model by its ID, sent as `ds://<id>`
+    pub fn fine_tuned(id: &str) -> Result<Self, ModelUriError> {
+        validate_segment(id)?;
+        Ok(ModelUri(Repr::Raw(format!("ds://{id}"))))
+    }
+
+    /// Returns the model of a foundation model or `gpt://` URI, `None` for fine-tuned models
+    pub fn model_type(&self) -> Option<ModelType> {
+        let name = match &self.0 {
+            Repr::Foundation { name, .. } => name.as_str(),
+            Repr::Raw(uri) => uri.strip_prefix("gpt://")?.split('/').nth(1)?,
+        };
+        name.parse().ok()
+    }
+
+    /// Returns the capabilities of the addressed model when it is a known preset
+    pub fn capabilities(&self) -> Option<ModelCapabilities> {
+        self.model_type()?.capabilities()
+    }
+
+    /// Returns the URI sent to the API, filling in the folder for foundation models
+    pub fn resolve(&self, folder_id: &str) -> String {
+        match &self.0 {
+            Repr::Foundation { name, version } => format!("gpt://{}/{}/{}", folder_id, name, version),
+            Repr::Raw(uri) => uri.clone(),
+        }
+    }
+}
+
+pub(crate) fn validate_segment(segment: &str) -> Result<(), ModelUriError> {
+    if segment.is_empty() {
+        return Err(ModelUriError::Empty);
+    }
+    if segment.contains('/') || segment.chars().any(char::is_whitespace) {
+        return Err(ModelUriError::Invalid(segment.to_string()));
+    }
+    Ok(())
+}
+
+impl FromStr for ModelUri {
+    type Err = ModelUriError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains("://") {
+            return ModelUri::raw(s);
+        }
+        match s.split_once('/') {
+            Some((name, version)) => ModelUri::new(name, version),
+            None => ModelUri::new(s, Version::Latest.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for ModelUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Repr::Foundation { name, version } => write!(f, "{}/{}", name, version),
+            Repr::Raw(uri) => write!(f, "{}", uri),
+        }
+    }
+}
+
+impl From<(ModelType, Version)> for ModelUri {
+    fn from((model, version): (ModelType, Version)) -> Self {
+        ModelUri::preset(model, version)
+    }
+}
+
+impl From<ModelType> for ModelUri {
+    fn from(model: ModelType) -> Self {
+        ModelUri::preset(model, Version::Latest)
+    }
+}
+
+impl Serialize for ModelUri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ModelUri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_resolve() {
+        let uri = ModelUri::from((ModelType::GptPro, Version::RC));
+        assert_eq!(uri.resolve("folder"), "gpt://folder/yandexgpt/rc");
+        assert_eq!(uri.to_string(), "yandexgpt/rc");
+    }
+
+    #[test]
+    fn test_parse_name_and_version() {
+        let uri: ModelUri = "yandexgpt-32k/rc".parse().unwrap();
+        assert_eq!(uri, ModelUri::new("yandexgpt-32k", "rc").unwrap());
+
+        let uri: ModelUri = "qwen3-235b-a22b-fp8".parse().unwrap();
+        assert_eq!(uri.resolve("folder"), "gpt://folder/qwen3-235b-a22b-fp8/latest");
+    }
+
+    #[test]
+    fn test_parse_raw() {
+        let uri: ModelUri = "ds://bt1abc".parse().unwrap();
+        assert_eq!(uri.resolve("ignored"), "ds://bt1abc");
+        assert_eq!(uri, ModelUri::fine_tuned("bt1abc").unwrap());
+
+        let uri: ModelUri = "gpt://b1g/yandexgpt-lite/latest".parse().unwrap();
+        assert_eq!(uri.to_string(), "gpt://b1g/yandexgpt-lite/latest");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<ModelUri>(), Err(ModelUriError::Empty));
+        assert_eq!("yandexgpt/".parse::<ModelUri>(), Err(ModelUriError::Empty));
+        assert_eq!(
+            "art://b1g/yandex-art".parse::<ModelUri>(),
+            Err(ModelUriError::UnsupportedScheme("art".to_string()))
+        );
+        assert!(matches!("gpt://b1g".parse::<ModelUri>(), Err(ModelUriError::Invalid(_))));
+        assert!(matches!("yandex gpt".parse::<ModelUri>(), Err(ModelUriError::Invalid(_))));
+        assert!(matches!("a/b/c".parse::<ModelUri>(), Err(ModelUriError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_model_type_and_capabilities() {
+        let uri: ModelUri = "gpt://b1g/yandexgpt/rc".parse().unwrap();
+        assert_eq!(uri.model_type(), Some(ModelType::GptPro));
+        assert!(uri.capabilities().unwrap().supports_tools);
+
+        let uri: ModelUri = "qwen3-235b-a22b-fp8".parse().unwrap();
+        assert_eq!(uri.model_type(), Some(ModelType::Custom("qwen3-235b-a22b-fp8".to_string())));
+        assert_eq!(uri.capabilities(), None);
+
+        assert_eq!(ModelUri::fine_tuned("bt1").unwrap().model_type(), None);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let uri = ModelUri::new("yandexgpt", "rc").unwrap();
+        let json = serde_json::to_string(&uri).unwrap();
+        assert_eq!(json, "\"yandexgpt/rc\"");
+        assert_eq!(serde_json::from_str::<ModelUri>(&json).unwrap(), uri);
+    }
+}
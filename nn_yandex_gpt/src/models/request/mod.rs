@@ -1,15 +1,19 @@
+pub mod validation;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use crate::models::message::Message;
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Request{
+    #[serde(default)]
     pub(crate) model_uri: String,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) completion_options: Option<CompletionOptions>,
 
+    #[serde(default)]
     pub messages: Vec<Message>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -39,6 +43,12 @@ pub struct RequestBuilder{
     tool_choice: Option<ToolChoice>,
 }
 
+impl Default for RequestBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RequestBuilder {
     pub fn new() -> Self {
         RequestBuilder {
@@ -94,9 +104,10 @@ impl RequestBuilder {
     }
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionOptions{
+    #[serde(default)]
     stream: bool,
 
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -111,6 +122,12 @@ pub struct CompletionOptions{
 
 }
 
+impl Default for CompletionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CompletionOptions {
     pub fn new() -> Self{
         CompletionOptions {
@@ -134,7 +151,7 @@ impl CompletionOptions {
     }
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum ReasoningMode{
     #[serde(rename = "REASONING_MODE_UNSPECIFIED")]
     ReasoningModeUnspecified,
@@ -146,19 +163,19 @@ pub enum ReasoningMode{
     EnabledHidden
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ReasoningOptions {
     pub mode: ReasoningMode,
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct FunctionWrapper {
     pub function: Function,
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Function {
     pub name: String,
@@ -167,16 +184,45 @@ pub struct Function {
     pub strict: bool,
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonSchema {
     pub schema: Value,
 }
 
-#[derive(Serialize, Clone, Debug)]
-pub enum ToolChoiceMode{
+/// Which tools the model may or must call
+///
+/// Serialized as `{"mode": "AUTO" | "NONE" | "REQUIRED"}` or `{"functionName": "<name>"}`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(into = "ToolChoiceWire", try_from = "ToolChoiceWire")]
+pub enum ToolChoice {
+    /// The model decides whether to call a tool
+    Auto,
+    /// The model must not call tools
+    None,
+    /// The model must call at least one tool
+    Required,
+    /// The model must call the named function
+    Function(String),
+}
+
+impl ToolChoice {
+    /// Forces a call of the given function
+    pub fn function(name: &str) -> Self {
+        ToolChoice::Function(name.to_string())
+    }
+}
+
+impl From<&FunctionWrapper> for ToolChoice {
+    fn from(tool: &FunctionWrapper) -> Self {
+        ToolChoice::Function(tool.function.name.clone())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+enum ToolChoiceMode{
     #[serde(rename = "TOOL_CHOICE_MODE_UNSPECIFIED")]
-    ToolChoiceModeUnspecified,
+    Unspecified,
 
     #[serde(rename = "AUTO")]
     Auto,
@@ -188,9 +234,80 @@ pub enum ToolChoiceMode{
     Required,
 }
 
-#[derive(Serialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct ToolChoice {
-    pub mode: ToolChoiceMode,
-    pub function_name: String,
-}
\ No newline at end of file
+struct ToolChoiceWire {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<ToolChoiceMode>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    function_name: Option<String>,
+}
+
+impl From<ToolChoice> for ToolChoiceWire {
+    fn from(choice: ToolChoice) -> Self {
+        let mode = |mode| ToolChoiceWire { mode: Some(mode), function_name: None };
+        match choice {
+            ToolChoice::Auto => mode(ToolChoiceMode::Auto),
+            ToolChoice::None => mode(ToolChoiceMode::None),
+            ToolChoice::Required => mode(ToolChoiceMode::Required),
+            ToolChoice::Function(name) => ToolChoiceWire { mode: None, function_name: Some(name) },
+        }
+    }
+}
+
+impl TryFrom<ToolChoiceWire> for ToolChoice {
+    type Error = String;
+
+    fn try_from(wire: ToolChoiceWire) -> Result<Self, Self::Error> {
+        if let Some(name) = wire.function_name.filter(|name| !name.is_empty()) {
+            return Ok(ToolChoice::Function(name));
+        }
+        match wire.mode {
+            Some(ToolChoiceMode::Auto | ToolChoiceMode::Unspecified) => Ok(ToolChoice::Auto),
+            Some(ToolChoiceMode::None) => Ok(ToolChoice::None),
+            Some(ToolChoiceMode::Required) => Ok(ToolChoice::Required),
+            None => Err("tool choice needs either mode or functionName".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tool_choice_serialization() {
+        assert_eq!(serde_json::to_value(ToolChoice::Auto).unwrap(), json!({"mode": "AUTO"}));
+        assert_eq!(serde_json::to_value(ToolChoice::None).unwrap(), json!({"mode": "NONE"}));
+        assert_eq!(serde_json::to_value(ToolChoice::Required).unwrap(), json!({"mode": "REQUIRED"}));
+        assert_eq!(
+            serde_json::to_value(ToolChoice::function("weather")).unwrap(),
+            json!({"functionName": "weather"})
+        );
+    }
+
+    #[test]
+    fn test_tool_choice_deserialization() {
+        let parse = |value| serde_json::from_value::<ToolChoice>(value);
+
+        assert_eq!(parse(json!({"mode": "REQUIRED"})).unwrap(), ToolChoice::Required);
+        assert_eq!(parse(json!({"functionName": "weather"})).unwrap(), ToolChoice::function("weather"));
+        assert_eq!(parse(json!({"mode": "NONE", "functionName": ""})).unwrap(), ToolChoice::None);
+        assert!(parse(json!({})).is_err());
+    }
+
+    #[test]
+    fn test_tool_choice_from_function() {
+        let tool = FunctionWrapper {
+            function: Function {
+                name: "weather".to_string(),
+                description: String::new(),
+                parameters: json!({}),
+                strict: false,
+            },
+        };
+        assert_eq!(ToolChoice::from(&tool), ToolChoice::function("weather"));
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+
+use crate::ModelCapabilities;
+use crate::models::request::{ReasoningMode, Request, ToolChoice};
+
+/// A single problem found in a request
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    /// The request has no messages
+    NoMessages,
+    /// `temperature` is outside `[0, 1]`
+    TemperatureOutOfRange(f64),
+    /// `max_tokens` is zero or negative
+    MaxTokensNotPositive(i64),
+    /// `max_tokens` is larger than the model's context length
+    MaxTokensExceedsContext { max_tokens: i64, context_length: u32 },
+    /// `tool_choice` names a function that is not in `tools`
+    UnknownToolChoice(String),
+    /// `tool_choice` is `Required` but no tools are given
+    ToolChoiceWithoutTools,
+    /// Both `json_object` and `json_schema` are set
+    ConflictingResponseFormats,
+    /// The model does not support function calling
+    ToolsNotSupported,
+    /// Reasoning is enabled for a model that does not support it
+    ReasoningNotSupported,
+    /// The model does not support `json_object` / `json_schema`
+    StructuredOutputNotSupported,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::NoMessages => write!(f, "request has no messages"),
+            Violation::TemperatureOutOfRange(t) => write!(f, "temperature {} is outside [0, 1]", t),
+            Violation::MaxTokensNotPositive(n) => write!(f, "max_tokens {} must be positive", n),
+            Violation::MaxTokensExceedsContext { max_tokens, context_length } => write!(
+                f,
+                "max_tokens {} exceeds the model context length {}",
+                max_tokens, context_length
+            ),
+            Violation::UnknownToolChoice(name) => write!(f, "tool_choice names unknown function {}", name),
+            Violation::ToolChoiceWithoutTools => write!(f, "tool_choice requires a tool call but no tools are given"),
+            Violation::ConflictingResponseFormats => write!(f, "json_object and json_schema are both set"),
+            Violation::ToolsNotSupported => write!(f, "model does not support tools"),
+            Violation::ReasoningNotSupported => write!(f, "model does not support reasoning"),
+            Violation::StructuredOutputNotSupported => write!(f, "model does not support structured output"),
+        }
+    }
+}
+
+/// Every problem found in a request
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid request: ")?;
+        for (index, violation) in self.violations.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl Request {
+    /// Checks the request before it is sent
+    ///
+    /// Model-specific checks run only when `capabilities` are known
+    /// (see `ModelUri::capabilities`); custom and fine-tuned models skip them.
+    pub fn validate(&self, capabilities: Option<&ModelCapabilities>) -> Result<(), ValidationError> {
+        let mut violations = Vec::new();
+
+        if self.messages.is_empty() {
+            violations.push(Violation::NoMessages);
+        }
+
+        let options = self.completion_options.as_ref();
+        if let Some(temperature) = options.and_then(|o| o.temperature)
+            && !(0.0..=1.0).contains(&temperature)
+        {
+            violations.push(Violation::TemperatureOutOfRange(temperature));
+        }
+        if let Some(max_tokens) = options.and_then(|o| o.max_tokens) {
+            if max_tokens <= 0 {
+                violations.push(Violation::MaxTokensNotPositive(max_tokens));
+            } else if let Some(caps) = capabilities.filter(|c| max_tokens > i64::from(c.context_length)) {
+                violations.push(Violation::MaxTokensExceedsContext {
+                    max_tokens,
+                    context_length: caps.context_length,
+                });
+            }
+        }
+
+        let tools = self.tools.as_deref().unwrap_or_default();
+        match &self.tool_choice {
+            Some(ToolChoice::Function(name)) if !tools.iter().any(|tool| &tool.function.name == name) => {
+                violations.push(Violation::UnknownToolChoice(name.clone()));
+            }
+            Some(ToolChoice::Required) if tools.is_empty() => {
+                violations.push(Violation::ToolChoiceWithoutTools);
+            }
+            _ => {}
+        }
+
+        let structured = self.json_object == Some(true) || self.json_schema.is_some();
+        if self.json_object == Some(true) && self.json_schema.is_some() {
+            violations.push(Violation::ConflictingResponseFormats);
+        }
+
+        if let Some(caps) = capabilities {
+            if !caps.supports_tools && !tools.is_empty() {
+                violations.push(Violation::ToolsNotSupported);
+            }
+            // Explicitly disabled or unspecified reasoning is accepted by every model
+            let reasoning = options
+                .and_then(|o| o.reasoning_options.as_ref())
+                .is_some_and(|r| matches!(r.mode, ReasoningMode::EnabledHidden));
+            if !caps.supports_reasoning && reasoning {
+                violations.push(Violation::ReasoningNotSupported);
+            }
+            if !caps.supports_structured_output && structured {
+                violations.push(Violation::StructuredOutputNotSupported);
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationError { violations })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ModelType;
+    use crate::models::message::MessageBuilder;
+    use crate::models::request::{
+        CompletionOptions, Function, FunctionWrapper, JsonSchema, ReasoningMode, ReasoningOptions,
+        RequestBuilder,
+    };
+    use serde_json::json;
+
+    fn tool(name: &str) -> FunctionWrapper {
+        FunctionWrapper {
+            function: Function {
+                name: name.to_string(),
+                description: String::new(),
+                parameters: json!({}),
+                strict: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_valid_request() {
+        let request = RequestBuilder::new()
+            .message(MessageBuilder::new().with_text("Hi").build())
+            .with_completion_options(CompletionOptions::new().with_temperature(0.5).with_max_tokens(100))
+            .build();
+
+        assert!(request.validate(ModelType::GptLite.capabilities().as_ref()).is_ok());
+    }
+
+    #[test]
+    fn test_reports_every_violation() {
+        let request = RequestBuilder::new()
+            .with_completion_options(CompletionOptions::new().with_temperature(1.5).with_max_tokens(-1))
+            .with_tools(vec![tool("weather")])
+            .with_tool_choice(ToolChoice::function("time"))
+            .with_json_object(true)
+            .with_json_schema(JsonSchema { schema: json!({}) })
+            .build();
+
+        let err = request.validate(None).unwrap_err();
+        assert_eq!(
+            err.violations,
+            vec![
+                Violation::NoMessages,
+                Violation::TemperatureOutOfRange(1.5),
+                Violation::MaxTokensNotPositive(-1),
+                Violation::UnknownToolChoice("time".to_string()),
+                Violation::ConflictingResponseFormats,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_required_tool_choice_without_tools() {
+        let request = RequestBuilder::new()
+            .message(MessageBuilder::new().with_text("Hi").build())
+            .with_tool_choice(ToolChoice::Required)
+            .build();
+
+        let err = request.validate(None).unwrap_err();
+        assert_eq!(err.violations, vec![Violation::ToolChoiceWithoutTools]);
+    }
+
+    #[test]
+    fn test_model_limits() {
+        let request = RequestBuilder::new()
+            .message(MessageBuilder::new().with_text("Hi").build())
+            .with_completion_options(
+                CompletionOptions::new()
+                    .with_max_tokens(10_000)
+                    .with_reasoning_options(ReasoningOptions { mode: ReasoningMode::EnabledHidden }),
+            )
+            .with_tools(vec![tool("weather")])
+            .build();
+
+        let err = request.validate(ModelType::Llama8B.capabilities().as_ref()).unwrap_err();
+        assert_eq!(
+            err.violations,
+            vec![
+                Violation::MaxTokensExceedsContext { max_tokens: 10_000, context_length: 8_192 },
+                Violation::ToolsNotSupported,
+                Violation::ReasoningNotSupported,
+            ]
+        );
+        assert!(request.validate(None).is_ok());
+    }
+
+    #[test]
+    fn test_disabled_reasoning_is_accepted() {
+        let request = RequestBuilder::new()
+            .message(MessageBuilder::new().with_text("Hi").build())
+            .with_completion_options(
+                CompletionOptions::new().with_reasoning_options(ReasoningOptions { mode: ReasoningMode::Disabled }),
+            )
+            .build();
+
+        assert!(request.validate(ModelType::GptLite.capabilities().as_ref()).is_ok());
+    }
+
+    #[test]
+    fn test_display_lists_violations() {
+        let err = ValidationError { violations: vec![Violation::NoMessages, Violation::ToolsNotSupported] };
+        assert_eq!(err.to_string(), "Invalid request: request has no messages; model does not support tools");
+    }
+}
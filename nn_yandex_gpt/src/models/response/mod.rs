@@ -1,15 +1,50 @@
-use crate::models::message::Message;
-use serde::Deserialize;
+use crate::models::message::{Message, ToolCallWrapper};
+use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
+use nn_yandex_core::usage::TokenUsage;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Result{
     pub result: ResultWrapper,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl Result {
+    /// Returns the first alternative, if any
+    pub fn alternative(&self) -> Option<&Alternative> {
+        self.result.alternatives.first()
+    }
+
+    /// Returns the text of the first alternative
+    pub fn text(&self) -> Option<&str> {
+        self.alternative().map(|alternative| alternative.message.text.as_str())
+    }
+
+    /// Returns the tool calls of the first alternative, empty if there are none
+    pub fn tool_calls(&self) -> &[ToolCallWrapper] {
+        self.alternative().map_or(&[], Alternative::tool_calls)
+    }
+
+    /// Returns the status of the first alternative
+    pub fn status(&self) -> Option<&AlternativeStatus> {
+        self.alternative().map(|alternative| &alternative.status)
+    }
+
+    /// `true` if the first alternative was cut off before the model finished
+    pub fn is_truncated(&self) -> bool {
+        self.status() == Some(&AlternativeStatus::TruncatedFinal)
+    }
+
+    /// `true` if the first alternative was blocked by the content filter
+    pub fn is_content_filtered(&self) -> bool {
+        self.status() == Some(&AlternativeStatus::ContentFilter)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ResultWrapper{
     pub alternatives: Vec<Alternative>,
@@ -17,21 +52,104 @@ pub struct ResultWrapper{
     pub model_version: String,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Error{
     pub code: String,
     pub message: String,    
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Alternative{
     pub message: Message,
+
+    #[serde(default)]
+    pub status: AlternativeStatus,
+}
+
+impl Alternative {
+    /// Returns the tool calls requested by the model, empty if there are none
+    pub fn tool_calls(&self) -> &[ToolCallWrapper] {
+        self.message
+            .tool_call_list
+            .as_ref()
+            .map_or(&[], |list| list.tool_calls.as_slice())
+    }
+}
+
+/// Why generation of an alternative stopped
+///
+/// Parses both `ALTERNATIVE_STATUS_FINAL` and `FINAL` forms; unrecognized values are kept in `Unknown`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum AlternativeStatus {
+    #[default]
+    Unspecified,
+    /// Part of the answer, returned while streaming
+    Partial,
+    /// The answer was cut off by `max_tokens` or the context length
+    TruncatedFinal,
+    /// The answer is complete
+    Final,
+    /// The answer was blocked by the content filter
+    ContentFilter,
+    /// The model requests tool calls
+    ToolCalls,
+    Unknown(String),
+}
+
+impl AlternativeStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AlternativeStatus::Unspecified => "ALTERNATIVE_STATUS_UNSPECIFIED",
+            AlternativeStatus::Partial => "ALTERNATIVE_STATUS_PARTIAL",
+            AlternativeStatus::TruncatedFinal => "ALTERNATIVE_STATUS_TRUNCATED_FINAL",
+            AlternativeStatus::Final => "ALTERNATIVE_STATUS_FINAL",
+            AlternativeStatus::ContentFilter => "ALTERNATIVE_STATUS_CONTENT_FILTER",
+            AlternativeStatus::ToolCalls => "ALTERNATIVE_STATUS_TOOL_CALLS",
+            AlternativeStatus::Unknown(status) => status,
+        }
+    }
+}
+
+impl FromStr for AlternativeStatus {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s.strip_prefix("ALTERNATIVE_STATUS_").unwrap_or(s) {
+            "UNSPECIFIED" => AlternativeStatus::Unspecified,
+            "PARTIAL" => AlternativeStatus::Partial,
+            "TRUNCATED_FINAL" => AlternativeStatus::TruncatedFinal,
+            "FINAL" => AlternativeStatus::Final,
+            "CONTENT_FILTER" => AlternativeStatus::ContentFilter,
+            "TOOL_CALLS" => AlternativeStatus::ToolCalls,
+            _ => AlternativeStatus::Unknown(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for AlternativeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for AlternativeStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AlternativeStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let Ok(status) = s.parse();
+        Ok(status)
+    }
 }
 
 #[serde_as]
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Usage{
     #[serde_as(as = "DisplayFromStr")]
@@ -44,9 +162,88 @@ pub struct Usage{
 }
 
 #[serde_as]
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CompletionTokensDetails{
     #[serde_as(as = "DisplayFromStr")]
     pub reasoning_tokens: i64,
-}
\ No newline at end of file
+}
+
+impl From<&Usage> for TokenUsage {
+    fn from(usage: &Usage) -> Self {
+        let count = |tokens: i64| tokens.max(0) as u64;
+        TokenUsage {
+            input_text_tokens: count(usage.input_text_tokens),
+            completion_tokens: count(usage.completion_tokens),
+            reasoning_tokens: usage
+                .completion_tokens_details
+                .as_ref()
+                .map_or(0, |details| count(details.reasoning_tokens)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(alternatives: &str) -> Result {
+        let json = format!(
+            r#"{{"result":{{"alternatives":{alternatives},"usage":{{"inputTextTokens":"1","completionTokens":"1","totalTokens":"2"}},"modelVersion":"1"}}}}"#
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_status_parsing() {
+        assert_eq!("ALTERNATIVE_STATUS_FINAL".parse(), Ok(AlternativeStatus::Final));
+        assert_eq!("TRUNCATED_FINAL".parse(), Ok(AlternativeStatus::TruncatedFinal));
+        assert_eq!("SOMETHING_NEW".parse(), Ok(AlternativeStatus::Unknown("SOMETHING_NEW".to_string())));
+    }
+
+    #[test]
+    fn test_accessors() {
+        let result = parse(r#"[{"message":{"role":"assistant","text":"Hi"},"status":"ALTERNATIVE_STATUS_TRUNCATED_FINAL"}]"#);
+
+        assert_eq!(result.text(), Some("Hi"));
+        assert!(result.is_truncated());
+        assert!(!result.is_content_filtered());
+        assert!(result.tool_calls().is_empty());
+    }
+
+    #[test]
+    fn test_tool_calls() {
+        let result = parse(
+            r#"[{"message":{"role":"assistant","text":"","toolCallList":{"toolCalls":[{"functionCall":{"name":"weather","arguments":{}}}]}},"status":"ALTERNATIVE_STATUS_TOOL_CALLS"}]"#,
+        );
+
+        assert_eq!(result.status(), Some(&AlternativeStatus::ToolCalls));
+        assert_eq!(result.tool_calls()[0].function_call.name, "weather");
+    }
+
+    #[test]
+    fn test_empty_alternatives() {
+        let result = parse("[]");
+
+        assert_eq!(result.text(), None);
+        assert_eq!(result.status(), None);
+        assert!(!result.is_truncated());
+        assert!(result.tool_calls().is_empty());
+    }
+
+    #[test]
+    fn test_missing_status_defaults_to_unspecified() {
+        let result = parse(r#"[{"message":{"role":"assistant","text":"Hi"}}]"#);
+        assert_eq!(result.status(), Some(&AlternativeStatus::Unspecified));
+    }
+
+    #[test]
+    fn test_usage_into_token_usage() {
+        let usage: Usage = serde_json::from_str(
+            r#"{"inputTextTokens":"10","completionTokens":"20","totalTokens":"30","completionTokensDetails":{"reasoningTokens":"5"}}"#,
+        )
+        .unwrap();
+        let tokens = TokenUsage::from(&usage);
+        assert_eq!((tokens.input_text_tokens, tokens.completion_tokens, tokens.reasoning_tokens), (10, 20, 5));
+    }
+}
@@ -1,2 +1,3 @@
 pub use nn_yandex_art;
+pub use nn_yandex_core;
 pub use nn_yandex_gpt;
\ No newline at end of file
@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use crate::template::VarType;
+
+/// Errors returned when building or rendering a template
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    /// The template source is not valid Jinja
+    Syntax(String),
+    /// The template uses variables that were not declared
+    Undeclared(Vec<String>),
+    /// A required variable was not supplied
+    Missing(String),
+    /// A supplied variable is not declared by the template
+    Unknown(String),
+    /// A value does not match the declared type of its variable
+    Type {
+        name: String,
+        expected: VarType,
+        found: &'static str,
+    },
+    /// The template failed while rendering
+    Render(String),
+    /// The rendered output could not be turned into messages
+    Message(String),
+}
+
+impl std::fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::Syntax(e) => write!(f, "Invalid template: {}", e),
+            TemplateError::Undeclared(names) => write!(f, "Undeclared variables: {}", names.join(", ")),
+            TemplateError::Missing(name) => write!(f, "Missing variable: {}", name),
+            TemplateError::Unknown(name) => write!(f, "Unknown variable: {}", name),
+            TemplateError::Type { name, expected, found } => {
+                write!(f, "Variable {} must be {}, got {}", name, expected, found)
+            }
+            TemplateError::Render(e) => write!(f, "Render error: {}", e),
+            TemplateError::Message(e) => write!(f, "Invalid message: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+/// Errors returned when loading prompts into a registry or looking them up
+#[derive(Debug)]
+pub enum RegistryError {
+    /// Failed to read a prompt file or directory
+    Io { path: PathBuf, source: std::io::Error },
+    /// A prompt file is not valid TOML/YAML or has unexpected fields
+    Parse { path: PathBuf, message: String },
+    /// A prompt definition has an invalid setting
+    Invalid { prompt: String, message: String },
+    /// The template of a prompt failed to build
+    Template { prompt: String, source: TemplateError },
+    /// A prompt version is defined twice
+    Duplicate(String),
+    /// No prompt matches the requested key
+    NotFound(String),
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::Io { path, source } => write!(f, "Failed to read {}: {}", path.display(), source),
+            RegistryError::Parse { path, message } => write!(f, "Invalid prompt file {}: {}", path.display(), message),
+            RegistryError::Invalid { prompt, message } => write!(f, "Invalid prompt {}: {}", prompt, message),
+            RegistryError::Template { prompt, source } => write!(f, "Invalid prompt {}: {}", prompt, source),
+            RegistryError::Duplicate(key) => write!(f, "Duplicate prompt: {}", key),
+            RegistryError::NotFound(key) => write!(f, "Prompt not found: {}", key),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RegistryError::Io { source, .. } => Some(source),
+            RegistryError::Template { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = TemplateError::Type {
+            name: "count".to_string(),
+            expected: VarType::List(Box::new(VarType::Integer)),
+            found: "string",
+        };
+        assert_eq!(err.to_string(), "Variable count must be list<integer>, got string");
+        assert_eq!(
+            TemplateError::Undeclared(vec!["a".to_string(), "b".to_string()]).to_string(),
+            "Undeclared variables: a, b"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use nn_yandex_art::models::request::message::{Message, MessageBuilder};
+use serde_json::Value;
+
+use crate::error::TemplateError;
+use crate::template::{Template, VarType, Variable, Vars, sections};
+
+const DEFAULT_WEIGHT: i64 = 1;
+
+/// Template rendering into weighted art messages
+///
+/// Each `<|weight=N|>` line starts a new message with weight `N`; text before the first
+/// marker has weight 1. Messages that render empty are dropped. Markers inside variable
+/// values are kept as text.
+///
+/// ```text
+/// {{ subject }} in {{ season }}
+/// {% if style %}
+/// <|weight=2|>
+/// {{ style }}
+/// {% endif %}
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImageTemplate {
+    template: Template,
+}
+
+impl ImageTemplate {
+    /// Renders the template into messages
+    pub fn render(&self, vars: &Vars) -> Result<Vec<Message>, TemplateError> {
+        let text = self.template.render(vars)?;
+        let sections = sections(&text, |line| {
+            let weight = line.strip_prefix("<|weight=")?.strip_suffix("|>")?;
+            Some(
+                weight
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| TemplateError::Message(format!("invalid weight: {}", weight))),
+            )
+        })?;
+
+        sections
+            .into_iter()
+            .map(|(weight, text)| {
+                MessageBuilder::new()
+                    .text(&text)
+                    .weight(weight.unwrap_or(DEFAULT_WEIGHT))
+                    .build()
+                    .map_err(|e| TemplateError::Message(e.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Builder for ImageTemplate
+/// `source` is required; every variable used by the template must be declared
+pub struct ImageTemplateBuilder {
+    source: String,
+    variables: Vec<Variable>,
+}
+
+impl ImageTemplateBuilder {
+    pub fn new(source: &str) -> Self {
+        ImageTemplateBuilder { source: source.to_string(), variables: Vec::new() }
+    }
+
+    /// Declares a required variable
+    pub fn variable(mut self, name: &str, ty: VarType) -> Self {
+        self.variables.push(Variable::required(name, ty));
+        self
+    }
+
+    /// Declares an optional variable; it is `none` in the template when not supplied
+    pub fn optional(mut self, name: &str, ty: VarType) -> Self {
+        self.variables.push(Variable::optional(name, ty));
+        self
+    }
+
+    /// Declares an optional variable with a default value
+    pub fn with_default(mut self, name: &str, ty: VarType, default: impl Into<Value>) -> Self {
+        self.variables.push(Variable::with_default(name, ty, default.into()));
+        self
+    }
+
+    /// Compiles the template and checks that all its variables are declared
+    pub fn build(self) -> Result<ImageTemplate, TemplateError> {
+        Ok(ImageTemplate { template: Template::compile(self.source, self.variables)? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template(source: &str) -> ImageTemplate {
+        ImageTemplateBuilder::new(source)
+            .variable("subject", VarType::String)
+            .with_default("season", VarType::String, "winter")
+            .optional("styles", VarType::List(Box::new(VarType::String)))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_weighted_messages() {
+        let template = template(
+            "{{ subject }} in {{ season }}\n{% for style in styles or [] %}\n<|weight=2|>\n{{ style }}\n{% endfor %}\n<|weight=-1|>\nblurry",
+        );
+        let messages = template.render(&Vars::new().with("subject", "fox").with("styles", json!(["watercolor"]))).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&messages).unwrap(),
+            json!([
+                {"text": "fox in winter", "weight": 1},
+                {"text": "watercolor", "weight": 2},
+                {"text": "blurry", "weight": -1},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_invalid_weight() {
+        let err = template("<|weight=heavy|>\n{{ subject }}").render(&Vars::new().with("subject", "fox")).unwrap_err();
+        assert_eq!(err, TemplateError::Message("invalid weight: heavy".to_string()));
+    }
+
+    #[test]
+    fn test_markers_in_values_are_text() {
+        let messages = template("{{ subject }}")
+            .render(&Vars::new().with("subject", "fox\n<|weight=heavy|>\n<|weight=-5|>\nforest"))
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&messages).unwrap(),
+            json!([{"text": "fox\n<|weight=heavy|>\n<|weight=-5|>\nforest", "weight": 1}])
+        );
+    }
+}
@@ -1,2 +1,34 @@
+//! Prompt templates for `nn_yandex_gpt` and `nn_yandex_art`
+//!
+//! Templates use Jinja syntax (`{{ var }}`, `{% if %}`, `{% for %}`) and declare their
+//! variables with a type. Using an undeclared variable is an error when the template is
+//! built; missing, unknown or mistyped values are errors when it is rendered.
+//!
+//! * `ChatTemplate` renders into a list of gpt `Message`s, one per `<|system|>`,
+//!   `<|user|>` or `<|assistant|>` marker line.
+//! * `ImageTemplate` renders into weighted art `Message`s, one per `<|weight=N|>` marker line.
+//! * `PromptRegistry` loads versioned, localized chat prompts with their model and request
+//!   settings from TOML/YAML files.
+
+pub mod error;
 pub mod image_generation;
-pub mod text_generation;
\ No newline at end of file
+pub mod registry;
+pub mod template;
+pub mod text_generation;
+
+pub use error::{RegistryError, TemplateError};
+pub use image_generation::{ImageTemplate, ImageTemplateBuilder};
+pub use registry::{Prompt, PromptRegistry};
+pub use template::{VarType, Vars};
+pub use text_generation::{ChatTemplate, ChatTemplateBuilder};
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ChatTemplate>();
+    assert_send_sync::<ImageTemplate>();
+    assert_send_sync::<Prompt>();
+    assert_send_sync::<PromptRegistry>();
+    assert_send_sync::<RegistryError>();
+    assert_send_sync::<TemplateError>();
+    assert_send_sync::<Vars>();
+};
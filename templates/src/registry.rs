@@ -0,0 +1,427 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use nn_yandex_gpt::models::model_uri::{ModelUri, ModelUriError};
+use nn_yandex_gpt::models::request::{CompletionOptions, Function, FunctionWrapper, JsonSchema, Request, RequestBuilder};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{RegistryError, TemplateError};
+use crate::template::{VarType, Vars};
+use crate::text_generation::{ChatTemplate, ChatTemplateBuilder};
+
+/// Prompt definition file
+///
+/// ```toml
+/// name = "summarize"
+///
+/// [versions.v3]
+/// model = "yandexgpt"
+/// version = "rc"
+/// locale = "ru"
+/// template = """
+/// <|system|>
+/// Кратко перескажи документ{% if words %} в {{ words }} словах{% endif %}.
+/// <|user|>
+/// {{ document }}
+/// """
+/// variables = { document = "string", words = "integer?" }
+/// defaults = { words = 100 }
+/// completion_options = { temperature = 0.3, maxTokens = 500 }
+///
+/// [versions.v3.locales.en]
+/// template = """
+/// <|system|>
+/// Summarize the document{% if words %} in {{ words }} words{% endif %}.
+/// <|user|>
+/// {{ document }}
+/// """
+/// ```
+///
+/// Variable types are `string`, `integer`, `float`, `bool`, `object`, `any` or `list<T>`;
+/// a trailing `?` makes the variable optional.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PromptFile {
+    name: String,
+    versions: BTreeMap<String, VersionDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VersionDef {
+    template: String,
+    model: Option<String>,
+    version: Option<String>,
+    locale: Option<String>,
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+    #[serde(default)]
+    defaults: BTreeMap<String, Value>,
+    completion_options: Option<CompletionOptions>,
+    json_schema: Option<Value>,
+    #[serde(default)]
+    tools: Vec<Function>,
+    #[serde(default)]
+    locales: BTreeMap<String, LocaleDef>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LocaleDef {
+    template: String,
+}
+
+/// Chat prompt with its model and request settings
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    name: String,
+    version: String,
+    locale: Option<String>,
+    template: ChatTemplate,
+    model: Option<ModelUri>,
+    completion_options: Option<CompletionOptions>,
+    json_schema: Option<JsonSchema>,
+    tools: Vec<FunctionWrapper>,
+}
+
+impl Prompt {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Default model of the prompt, to be passed to `TextGenerator::complete`
+    pub fn model(&self) -> Option<&ModelUri> {
+        self.model.as_ref()
+    }
+
+    /// Renders the template into a request with the prompt's completion options,
+    /// JSON schema and tools
+    pub fn render(&self, vars: &Vars) -> Result<Request, TemplateError> {
+        let mut builder = self.template.render(vars)?.into_iter().fold(RequestBuilder::new(), |b, m| b.message(m));
+        if let Some(options) = &self.completion_options {
+            builder = builder.with_completion_options(options.clone());
+        }
+        if let Some(schema) = &self.json_schema {
+            builder = builder.with_json_schema(schema.clone());
+        }
+        if !self.tools.is_empty() {
+            builder = builder.with_tools(self.tools.clone());
+        }
+        Ok(builder.build())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Versioned {
+    prompt: Prompt,
+    locales: BTreeMap<String, Prompt>,
+}
+
+/// Named, versioned and localized prompts
+///
+/// Prompts are looked up by `name` (latest version) or `name@version`. Versions are ordered
+/// by their number (`v2` < `v10`), falling back to text order. `get` returns the variant for
+/// the registry locale when one exists and the prompt's own template otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct PromptRegistry {
+    prompts: BTreeMap<String, BTreeMap<String, Versioned>>,
+    locale: Option<String>,
+}
+
+impl PromptRegistry {
+    pub fn new() -> Self {
+        PromptRegistry { prompts: BTreeMap::new(), locale: None }
+    }
+
+    /// Loads every `.toml`, `.yaml` and `.yml` file in `dir`
+    pub fn load_dir(dir: &Path) -> Result<Self, RegistryError> {
+        let io_error = |source| RegistryError::Io { path: dir.to_path_buf(), source };
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map_err(io_error)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()
+            .map_err(io_error)?;
+        paths.sort();
+
+        let mut registry = PromptRegistry::new();
+        for path in paths {
+            if matches!(path.extension().and_then(|ext| ext.to_str()), Some("toml" | "yaml" | "yml")) {
+                registry.load_file(&path)?;
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Loads a TOML or YAML prompt file, chosen by its extension
+    pub fn load_file(&mut self, path: &Path) -> Result<(), RegistryError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|source| RegistryError::Io { path: path.to_path_buf(), source })?;
+        let parse_error = |message: String| RegistryError::Parse { path: path.to_path_buf(), message };
+        let file = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(|e| parse_error(e.to_string()))?,
+            _ => toml::from_str(&text).map_err(|e| parse_error(e.to_string()))?,
+        };
+        self.add(file)
+    }
+
+    /// Adds the prompts of a TOML definition
+    pub fn add_toml(&mut self, text: &str) -> Result<(), RegistryError> {
+        let file = toml::from_str(text)
+            .map_err(|e| RegistryError::Parse { path: PathBuf::from("<toml>"), message: e.to_string() })?;
+        self.add(file)
+    }
+
+    /// Adds the prompts of a YAML definition
+    pub fn add_yaml(&mut self, text: &str) -> Result<(), RegistryError> {
+        let file = serde_yaml::from_str(text)
+            .map_err(|e| RegistryError::Parse { path: PathBuf::from("<yaml>"), message: e.to_string() })?;
+        self.add(file)
+    }
+
+    /// Sets the locale whose variants `get` prefers
+    pub fn with_locale(mut self, locale: &str) -> Self {
+        self.locale = Some(locale.to_string());
+        self
+    }
+
+    /// Returns the prompt for `name` or `name@version` in the registry locale
+    pub fn get(&self, key: &str) -> Result<&Prompt, RegistryError> {
+        let versioned = self.find(key)?;
+        Ok(self.locale.as_ref().and_then(|locale| versioned.locales.get(locale)).unwrap_or(&versioned.prompt))
+    }
+
+    /// Returns the prompt for `name` or `name@version` in `locale`
+    pub fn get_locale(&self, key: &str, locale: &str) -> Result<&Prompt, RegistryError> {
+        let versioned = self.find(key)?;
+        if versioned.prompt.locale.as_deref() == Some(locale) {
+            return Ok(&versioned.prompt);
+        }
+        versioned.locales.get(locale).ok_or_else(|| RegistryError::NotFound(format!("{key} ({locale})")))
+    }
+
+    /// Returns the versions of a prompt, oldest first
+    pub fn versions(&self, name: &str) -> Vec<&str> {
+        let mut versions: Vec<&str> = self.prompts.get(name).into_iter().flat_map(|v| v.keys().map(String::as_str)).collect();
+        versions.sort_by_key(|v| version_order(v));
+        versions
+    }
+
+    fn find(&self, key: &str) -> Result<&Versioned, RegistryError> {
+        let not_found = || RegistryError::NotFound(key.to_string());
+        let (name, version) = match key.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (key, None),
+        };
+        let versions = self.prompts.get(name).ok_or_else(not_found)?;
+        match version {
+            Some(version) => versions.get(version).ok_or_else(not_found),
+            None => versions.iter().max_by_key(|(v, _)| version_order(v)).map(|(_, p)| p).ok_or_else(not_found),
+        }
+    }
+
+    /// Adds every version of `file` or, on error, none of them
+    fn add(&mut self, file: PromptFile) -> Result<(), RegistryError> {
+        let existing = self.prompts.get(&file.name);
+        if let Some(version) = file.versions.keys().find(|v| existing.is_some_and(|e| e.contains_key(*v))) {
+            return Err(RegistryError::Duplicate(format!("{}@{}", file.name, version)));
+        }
+
+        let versions = file
+            .versions
+            .into_iter()
+            .map(|(version, def)| Ok((version.clone(), build_version(&file.name, &version, def)?)))
+            .collect::<Result<Vec<_>, RegistryError>>()?;
+        self.prompts.entry(file.name).or_default().extend(versions);
+        Ok(())
+    }
+}
+
+/// Orders `v2` before `v10`; versions without a number come first in text order
+fn version_order(version: &str) -> (Option<u64>, &str) {
+    (version.trim_start_matches('v').parse().ok(), version)
+}
+
+fn build_version(name: &str, version: &str, def: VersionDef) -> Result<Versioned, RegistryError> {
+    let key = format!("{name}@{version}");
+    let invalid = |message: String| RegistryError::Invalid { prompt: key.clone(), message };
+
+    let model = match (def.model.as_deref(), def.version.as_deref()) {
+        (None, None) => None,
+        (None, Some(_)) => return Err(invalid("version is set without a model".to_string())),
+        (Some(model), None) => Some(model.parse().map_err(|e: ModelUriError| invalid(e.to_string()))?),
+        (Some(model), Some(v)) => Some(ModelUri::new(model, v).map_err(|e| invalid(e.to_string()))?),
+    };
+
+    let mut variables = Vec::new();
+    for (var, ty) in &def.variables {
+        let (ty, optional) = match ty.strip_suffix('?') {
+            Some(ty) => (ty, true),
+            None => (ty.as_str(), false),
+        };
+        let ty: VarType = ty.parse().map_err(|e: TemplateError| invalid(e.to_string()))?;
+        variables.push((var.clone(), ty, optional || def.defaults.contains_key(var)));
+    }
+    if let Some(var) = def.defaults.keys().find(|var| !def.variables.contains_key(*var)) {
+        return Err(invalid(format!("default for undeclared variable {var}")));
+    }
+
+    let template = |source: &str| {
+        let mut builder = ChatTemplateBuilder::new(source);
+        for (var, ty, optional) in &variables {
+            builder = match (def.defaults.get(var), optional) {
+                (Some(default), _) => builder.with_default(var, ty.clone(), default.clone()),
+                (None, true) => builder.optional(var, ty.clone()),
+                (None, false) => builder.variable(var, ty.clone()),
+            };
+        }
+        builder.build().map_err(|source| RegistryError::Template { prompt: key.clone(), source })
+    };
+
+    let prompt = Prompt {
+        name: name.to_string(),
+        version: version.to_string(),
+        locale: def.locale.clone(),
+        template: template(&def.template)?,
+        model,
+        completion_options: def.completion_options,
+        json_schema: def.json_schema.map(|schema| JsonSchema { schema }),
+        tools: def.tools.into_iter().map(|function| FunctionWrapper { function }).collect(),
+    };
+
+    let mut locales = BTreeMap::new();
+    for (locale, variant) in def.locales {
+        let template = template(&variant.template)?;
+        locales.insert(locale.clone(), Prompt { locale: Some(locale), template, ..prompt.clone() });
+    }
+
+    Ok(Versioned { prompt, locales })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUMMARIZE: &str = r#"
+name = "summarize"
+
+[versions.v2]
+template = "Summarize: {{ document }}"
+variables = { document = "string" }
+
+[versions.v10]
+model = "yandexgpt"
+version = "rc"
+locale = "ru"
+template = """
+<|system|>
+Перескажи документ{% if words %} в {{ words }} словах{% endif %}.
+<|user|>
+{{ document }}
+"""
+variables = { document = "string", words = "integer?" }
+defaults = { words = 100 }
+completion_options = { temperature = 0.3, maxTokens = 500 }
+json_schema = { type = "object" }
+
+[[versions.v10.tools]]
+name = "save"
+description = "Saves the summary"
+parameters = { type = "object" }
+strict = false
+
+[versions.v10.locales.en]
+template = """
+<|system|>
+Summarize the document{% if words %} in {{ words }} words{% endif %}.
+<|user|>
+{{ document }}
+"""
+"#;
+
+    fn registry() -> PromptRegistry {
+        let mut registry = PromptRegistry::new();
+        registry.add_toml(SUMMARIZE).unwrap();
+        registry
+    }
+
+    #[test]
+    fn test_render_request() {
+        let registry = registry();
+        let prompt = registry.get("summarize@v10").unwrap();
+        let request = prompt.render(&Vars::new().with("document", "Текст")).unwrap();
+
+        assert_eq!(prompt.model().unwrap().to_string(), "yandexgpt/rc");
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["messages"][0]["text"], "Перескажи документ в 100 словах.");
+        assert_eq!(body["messages"][1]["text"], "Текст");
+        assert_eq!(body["completionOptions"]["maxTokens"], 500);
+        assert_eq!(body["jsonSchema"]["schema"]["type"], "object");
+        assert_eq!(body["tools"][0]["function"]["name"], "save");
+    }
+
+    #[test]
+    fn test_versions_and_locales() {
+        let registry = registry();
+
+        assert_eq!(registry.versions("summarize"), vec!["v2", "v10"]);
+        assert_eq!(registry.get("summarize").unwrap().version(), "v10");
+        assert_eq!(registry.get_locale("summarize", "ru").unwrap().locale(), Some("ru"));
+        assert!(matches!(registry.get_locale("summarize@v2", "en"), Err(RegistryError::NotFound(_))));
+
+        let english = registry.clone().with_locale("en");
+        let request = english.get("summarize").unwrap().render(&Vars::new().with("document", "Text")).unwrap();
+        assert_eq!(request.messages[0].text, "Summarize the document in 100 words.");
+        assert_eq!(english.get("summarize@v2").unwrap().locale(), None);
+    }
+
+    #[test]
+    fn test_yaml_and_errors() {
+        let mut registry = registry();
+        registry
+            .add_yaml("name: greet\nversions:\n  v1:\n    template: \"Hi {{ name }}\"\n    variables:\n      name: string\n")
+            .unwrap();
+        assert!(registry.get("greet@v1").is_ok());
+
+        assert!(matches!(registry.add_toml(SUMMARIZE), Err(RegistryError::Duplicate(key)) if key == "summarize@v10"));
+        assert!(matches!(registry.get("summarize@v3"), Err(RegistryError::NotFound(_))));
+        let undeclared = "name = \"x\"\n[versions.v1]\ntemplate = \"{{ missing }}\"";
+        assert!(matches!(
+            registry.add_toml(undeclared),
+            Err(RegistryError::Template { source: TemplateError::Undeclared(_), .. })
+        ));
+        assert!(matches!(registry.add_toml("name = \"x\"\nversions = 1"), Err(RegistryError::Parse { .. })));
+    }
+
+    #[test]
+    fn test_failed_add_changes_nothing() {
+        let mut registry = registry();
+        let invalid_v2 = "name = \"greet\"\n[versions.v1]\ntemplate = \"Hi\"\n[versions.v2]\ntemplate = \"{{ missing }}\"";
+        assert!(matches!(registry.add_toml(invalid_v2), Err(RegistryError::Template { .. })));
+        assert!(registry.versions("greet").is_empty());
+
+        let duplicate_v10 = "name = \"summarize\"\n[versions.v1]\ntemplate = \"Hi\"\n[versions.v10]\ntemplate = \"Hi\"";
+        assert!(matches!(registry.add_toml(duplicate_v10), Err(RegistryError::Duplicate(key)) if key == "summarize@v10"));
+        assert_eq!(registry.versions("summarize"), vec!["v2", "v10"]);
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = std::env::temp_dir().join(format!("nn_yandex_prompts_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("summarize.toml"), SUMMARIZE).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let registry = PromptRegistry::load_dir(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(registry.unwrap().versions("summarize").len(), 2);
+    }
+}